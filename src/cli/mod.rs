@@ -3,6 +3,9 @@
 //! This module contains all command-line interface functionality:
 //! - CLI argument parsing and command dispatch
 //! - Output styling and formatting options
+//! - Machine-readable (JSON/YAML) output
 
+#[allow(clippy::module_inception)]
 pub mod cli;
+pub mod output;
 pub mod styles;
@@ -0,0 +1,54 @@
+//! Machine-readable output selected with the global `--output` flag.
+//!
+//! With `json` or `yaml`, commands that report data print one document on
+//! stdout and the decorated `styles` messages are suppressed, so scripts never
+//! have to parse emoji text. Errors are reported as `{"error": "..."}` on
+//! stderr. Field sets are part of the public interface: add fields, never
+//! rename or drop them.
+
+use crate::cli::cli::OutputFormat;
+use anyhow::Result;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+
+static FORMAT: OnceCell<OutputFormat> = OnceCell::new();
+
+/// Set the output format once at startup.
+pub fn init(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+/// The selected output format (`table` until initialized).
+pub fn format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or(OutputFormat::Table)
+}
+
+/// Whether a machine-readable format was requested.
+pub fn is_structured() -> bool {
+    format() != OutputFormat::Table
+}
+
+fn render<T: Serialize>(value: &T) -> Result<String> {
+    Ok(match format() {
+        OutputFormat::Yaml => serde_yaml::to_string(value)?,
+        _ => format!("{}\n", serde_json::to_string_pretty(value)?),
+    })
+}
+
+/// Print a document on stdout in the selected format.
+pub fn emit<T: Serialize>(value: &T) -> Result<()> {
+    print!("{}", render(value)?);
+    Ok(())
+}
+
+/// Print an error document on stderr in the selected format.
+pub fn emit_error(message: &str) {
+    #[derive(Serialize)]
+    struct ErrorDoc<'a> {
+        error: &'a str,
+    }
+    match render(&ErrorDoc { error: message }) {
+        Ok(doc) => eprint!("{}", doc),
+        Err(_) => eprintln!("safehold error: {message}"),
+    }
+}
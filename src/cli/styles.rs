@@ -39,9 +39,14 @@ fn opts() -> StyleOptions {
     })
 }
 
+/// Whether stdout decorations are suppressed (`--quiet` or `--output json|yaml`).
+fn silent() -> bool {
+    opts().quiet || crate::cli::output::is_structured()
+}
+
 /// Success messages with green checkmark
 pub fn success<S: AsRef<str>>(msg: S) {
-    if opts().quiet {
+    if silent() {
         return;
     }
     let m = msg.as_ref();
@@ -61,7 +66,7 @@ pub fn ok<S: AsRef<str>>(msg: S) {
 
 /// Info messages with blue information icon
 pub fn info<S: AsRef<str>>(msg: S) {
-    if opts().quiet {
+    if silent() {
         return;
     }
     let m = msg.as_ref();
@@ -113,7 +118,7 @@ pub fn err<S: AsRef<str>>(msg: S) {
 /// Debug messages with purple debug icon
 #[allow(dead_code)]
 pub fn debug<S: AsRef<str>>(msg: S) {
-    if opts().quiet {
+    if silent() {
         return;
     }
     let m = msg.as_ref();
@@ -128,7 +133,7 @@ pub fn debug<S: AsRef<str>>(msg: S) {
 
 /// Print a header with decorative border
 pub fn header<S: AsRef<str>>(msg: S) {
-    if opts().quiet {
+    if silent() {
         return;
     }
     let m = msg.as_ref();
@@ -143,15 +148,15 @@ pub fn header<S: AsRef<str>>(msg: S) {
         println!("{}", format!("│ {} │", m).cyan());
         println!("{}", format!("╰{}╯", line).cyan());
     } else {
-        println!("{}", format!("+{}+", line.replace("─", "-")));
+        println!("+{}+", line.replace("─", "-"));
         println!("| {} |", m);
-        println!("{}", format!("+{}+", line.replace("─", "-")));
+        println!("+{}+", line.replace("─", "-"));
     }
 }
 
 /// Print a simple divider
 pub fn divider() {
-    if opts().quiet {
+    if silent() {
         return;
     }
     if opts().use_color {
@@ -163,7 +168,7 @@ pub fn divider() {
 
 /// Print key-value pairs with nice formatting
 pub fn kv<K: AsRef<str>, V: AsRef<str>>(key: K, value: V) {
-    if opts().quiet {
+    if silent() {
         return;
     }
     let k = key.as_ref();
@@ -180,7 +185,7 @@ pub fn kv<K: AsRef<str>, V: AsRef<str>>(key: K, value: V) {
 
 /// Print a bullet point item
 pub fn bullet<S: AsRef<str>>(msg: S) {
-    if opts().quiet {
+    if silent() {
         return;
     }
     let m = msg.as_ref();
@@ -25,6 +25,27 @@ pub struct SetMeta {
     pub id: String,   // e.g. 001_project1
     pub name: String, // display name
     pub locked: bool, // requires password
+    /// Hidden from listings until unarchived
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// Free-form note shown in listings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Grouping path such as `clients/acme/prod`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+}
+
+impl SetMeta {
+    /// Whether the project sits in `folder` or one of its subfolders.
+    pub fn in_folder(&self, folder: &str) -> bool {
+        let folder = folder.trim_matches('/');
+        self.folder.as_deref().is_some_and(|f| {
+            f == folder || (f.starts_with(folder) && f[folder.len()..].starts_with('/'))
+        })
+    }
 }
 
 fn default_version() -> String {
@@ -129,12 +150,11 @@ pub fn global_dir() -> Result<PathBuf> {
 pub fn next_set_id(name: &str, existing: &[SetMeta]) -> String {
     let mut maxn = 0u32;
     for s in existing {
-        if let Some((n, _)) = s.id.split_once('_') {
-            if let Ok(v) = n.parse::<u32>() {
-                if v > maxn {
-                    maxn = v;
-                }
-            }
+        if let Some((n, _)) = s.id.split_once('_')
+            && let Ok(v) = n.parse::<u32>()
+            && v > maxn
+        {
+            maxn = v;
         }
     }
     format!("{:03}_{}", maxn + 1, name)
@@ -146,6 +166,18 @@ pub fn lock_path(dir: &Path) -> PathBuf {
 pub fn env_enc_path(dir: &Path) -> PathBuf {
     dir.join(".env.enc")
 }
+pub fn policy_path(dir: &Path) -> PathBuf {
+    dir.join("policy.json")
+}
+pub fn key_meta_path(dir: &Path) -> PathBuf {
+    dir.join("keys.enc")
+}
+pub fn history_path(dir: &Path) -> PathBuf {
+    dir.join("history.enc")
+}
+pub fn schema_path(dir: &Path) -> PathBuf {
+    dir.join("schema.json")
+}
 
 /// Version tracking for backward compatibility
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -220,12 +252,12 @@ pub fn display_version_message(old_version: &str) -> Result<()> {
     println!("│                    🔄 SafeHold Version Update                    │");
     println!("│                                                                 │");
     println!(
-        "│  Previous Version: {}                                        │",
-        format!("{:<10}", old_version)
+        "│  Previous Version: {:<10}                                        │",
+        old_version
     );
     println!(
-        "│  Current Version:  {}                                        │",
-        format!("{:<10}", env!("CARGO_PKG_VERSION"))
+        "│  Current Version:  {:<10}                                        │",
+        env!("CARGO_PKG_VERSION")
     );
     println!("│                                                                 │");
     println!("│  ✅ Your data has been preserved and is compatible             │");
@@ -238,7 +270,7 @@ pub fn display_version_message(old_version: &str) -> Result<()> {
     println!("└─────────────────────────────────────────────────────────────────┘");
     println!();
 
-    styles::success(&format!(
+    styles::success(format!(
         "🎉 Update completed successfully! Welcome to SafeHold v{}",
         env!("CARGO_PKG_VERSION")
     ));
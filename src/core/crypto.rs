@@ -1,4 +1,5 @@
 //! Cryptography utilities: app key management and password KDF + AEAD.
+use crate::core::secret::SecretKey;
 use aes_gcm::{
     Aes256Gcm, Nonce,
     aead::{Aead, KeyInit},
@@ -12,7 +13,7 @@ use rand::{RngCore, rng};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
 
 // App-managed key is stored in base dir as app.key (random 32 bytes)
 const APP_KEY_FILE: &str = "app.key";
@@ -50,26 +51,29 @@ pub fn ensure_app_key(base: &Path) -> Result<()> {
     if !key_path.exists() {
         let mut key = [0u8; AES_KEY_SIZE];
         rng().fill_bytes(&mut key);
-        fs::write(&key_path, &key)?;
+        fs::write(&key_path, key)?;
         key.zeroize();
     }
     Ok(())
 }
 
 /// Load the 32-byte app key from disk.
-pub fn load_app_key(base: &Path) -> Result<[u8; AES_KEY_SIZE]> {
-    let data = fs::read(base.join(APP_KEY_FILE)).map_err(|e| anyhow!("read app.key: {e}"))?;
+pub fn load_app_key(base: &Path) -> Result<SecretKey> {
+    let data = Zeroizing::new(
+        fs::read(base.join(APP_KEY_FILE)).map_err(|e| anyhow!("read app.key: {e}"))?,
+    );
     if data.len() != AES_KEY_SIZE {
         bail!("invalid app.key size")
     }
-    let mut key = [0u8; AES_KEY_SIZE];
-    key.copy_from_slice(&data);
+    let mut key = SecretKey::new([0u8; AES_KEY_SIZE]);
+    key.expose_mut().copy_from_slice(&data);
     Ok(key)
 }
 
 /// Encrypt plaintext with AES-256-GCM, prepending a random 12-byte nonce.
-pub fn encrypt_with_key(key: &[u8; AES_KEY_SIZE], plaintext: &[u8]) -> Result<Vec<u8>> {
-    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("cipher init: {e}"))?;
+pub fn encrypt_with_key(key: &SecretKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let cipher =
+        Aes256Gcm::new_from_slice(key.expose()).map_err(|e| anyhow!("cipher init: {e}"))?;
     let mut nonce_bytes = [0u8; AES_GCM_NONCE_SIZE];
     rng().fill_bytes(&mut nonce_bytes);
     let nonce = Nonce::from_slice(&nonce_bytes);
@@ -82,22 +86,23 @@ pub fn encrypt_with_key(key: &[u8; AES_KEY_SIZE], plaintext: &[u8]) -> Result<Ve
     Ok(out)
 }
 
-/// Decrypt data produced by `encrypt_with_key`.
-pub fn decrypt_with_key(key: &[u8; AES_KEY_SIZE], data: &[u8]) -> Result<Vec<u8>> {
+/// Decrypt data produced by `encrypt_with_key`; the plaintext is wiped when dropped.
+pub fn decrypt_with_key(key: &SecretKey, data: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
     if data.len() < AES_GCM_NONCE_SIZE {
         bail!("cipher too short")
     }
     let (nonce_bytes, ct) = data.split_at(AES_GCM_NONCE_SIZE);
-    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("cipher init: {e}"))?;
+    let cipher =
+        Aes256Gcm::new_from_slice(key.expose()).map_err(|e| anyhow!("cipher init: {e}"))?;
     let nonce = Nonce::from_slice(nonce_bytes);
     let pt = cipher
         .decrypt(nonce, ct)
         .map_err(|e| anyhow!("decrypt: {e}"))?;
-    Ok(pt)
+    Ok(Zeroizing::new(pt))
 }
 
 /// Derive a 32-byte key from `password` using the provided `LockInfo`.
-pub fn derive_key_from_password(password: &str, lock: &LockInfo) -> Result<[u8; AES_KEY_SIZE]> {
+pub fn derive_key_from_password(password: &str, lock: &LockInfo) -> Result<SecretKey> {
     let salt_bytes = B64
         .decode(&lock.salt_b64)
         .map_err(|e| anyhow!("salt b64: {e}"))?;
@@ -115,18 +120,15 @@ pub fn derive_key_from_password(password: &str, lock: &LockInfo) -> Result<[u8;
         params,
     )
     .map_err(|e| anyhow!("argon: {e}"))?;
-    let mut out = [0u8; ARGON2_OUTPUT_SIZE];
+    let mut out = SecretKey::new([0u8; ARGON2_OUTPUT_SIZE]);
     argon
-        .hash_password_into(password.as_bytes(), &salt_bytes, &mut out)
+        .hash_password_into(password.as_bytes(), &salt_bytes, out.expose_mut())
         .map_err(|e| anyhow!("derive: {e}"))?;
     Ok(out)
 }
 
 /// Derive a 32-byte key from `password` using a custom salt (for master lock).
-pub fn derive_key_from_password_and_salt(
-    password: &str,
-    salt: &[u8],
-) -> Result<[u8; AES_KEY_SIZE]> {
+pub fn derive_key_from_password_and_salt(password: &str, salt: &[u8]) -> Result<SecretKey> {
     // Use consistent parameters for master lock derivation
     let params = argon2::Params::new(
         DEFAULT_ARGON2_M_COST,
@@ -142,9 +144,9 @@ pub fn derive_key_from_password_and_salt(
         params,
     )
     .map_err(|e| anyhow!("argon: {e}"))?;
-    let mut out = [0u8; ARGON2_OUTPUT_SIZE];
+    let mut out = SecretKey::new([0u8; ARGON2_OUTPUT_SIZE]);
     argon
-        .hash_password_into(password.as_bytes(), salt, &mut out)
+        .hash_password_into(password.as_bytes(), salt, out.expose_mut())
         .map_err(|e| anyhow!("derive: {e}"))?;
     Ok(out)
 }
@@ -173,9 +175,9 @@ pub fn create_lock(password: &str) -> Result<LockInfo> {
     )
     .map_err(|e| anyhow!("argon: {e}"))?;
     // derive once to validate
-    let mut out = [0u8; ARGON2_OUTPUT_SIZE];
+    let mut out = SecretKey::new([0u8; ARGON2_OUTPUT_SIZE]);
     argon
-        .hash_password_into(password.as_bytes(), &salt, &mut out)
+        .hash_password_into(password.as_bytes(), &salt, out.expose_mut())
         .map_err(|e| anyhow!("derive: {e}"))?;
     Ok(LockInfo {
         kdf: "argon2id".into(),
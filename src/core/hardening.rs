@@ -0,0 +1,245 @@
+//! Process hardening while SafeHold holds decrypted material.
+//!
+//! At startup core dumps are switched off (the soft `RLIMIT_CORE`, so commands
+//! started by `run` get the original limit back) and, on Linux, the process is
+//! marked non-dumpable, which also stops same-user processes from attaching
+//! with `ptrace` or reading `/proc/<pid>/mem`. Memory holding derived keys and
+//! decrypted values is `mlock`ed so it is never written to swap; pages are
+//! reference-counted because several small buffers can share one, and only
+//! regions that were locked can be released.
+//!
+//! Every step is best effort: a failure is reported by `about`, never fatal.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Which protections are active in this process.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Protections {
+    pub core_dumps_disabled: bool,
+    pub non_dumpable: bool,
+    pub memory_locking: bool,
+}
+
+impl Protections {
+    /// One-line summary for `about`.
+    pub fn summary(&self) -> String {
+        let state = |on: bool| if on { "on" } else { "off" };
+        format!(
+            "core dumps disabled: {}, ptrace/dump blocked: {}, memory locking: {}",
+            state(self.core_dumps_disabled),
+            state(self.non_dumpable),
+            state(self.memory_locking)
+        )
+    }
+}
+
+static APPLIED: Mutex<Option<Protections>> = Mutex::new(None);
+/// Set when an `mlock` call failed (e.g. `RLIMIT_MEMLOCK` exhausted).
+static LOCK_FAILED: AtomicBool = AtomicBool::new(false);
+#[cfg(unix)]
+static LOCKED: Mutex<Locked> = Mutex::new(Locked {
+    pages: BTreeMap::new(),
+    regions: BTreeMap::new(),
+});
+
+#[cfg(unix)]
+/// Live locks: page address and `(ptr, len)` region -> number of holders.
+struct Locked {
+    pages: BTreeMap<usize, usize>,
+    regions: BTreeMap<(usize, usize), usize>,
+}
+/// Soft core limit before `apply`, restored in child processes.
+#[cfg(unix)]
+static ORIGINAL_CORE_LIMIT: Mutex<Option<libc::rlimit>> = Mutex::new(None);
+
+/// Harden the current process; call once, before anything is decrypted.
+pub fn apply() -> Protections {
+    let protections = Protections {
+        core_dumps_disabled: disable_core_dumps(),
+        non_dumpable: set_non_dumpable(),
+        memory_locking: probe_memory_locking(),
+    };
+    *APPLIED.lock().unwrap_or_else(|e| e.into_inner()) = Some(protections);
+    protections
+}
+
+/// Protections currently in effect (all off if `apply` was never called).
+pub fn status() -> Protections {
+    let applied = APPLIED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or_default();
+    Protections {
+        memory_locking: applied.memory_locking && !LOCK_FAILED.load(Ordering::Relaxed),
+        ..applied
+    }
+}
+
+#[cfg(unix)]
+fn disable_core_dumps() -> bool {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: plain syscalls on a valid, owned struct
+    unsafe {
+        if libc::getrlimit(libc::RLIMIT_CORE, &mut limit) != 0 {
+            return false;
+        }
+        *ORIGINAL_CORE_LIMIT
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(limit);
+        let off = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: limit.rlim_max,
+        };
+        libc::setrlimit(libc::RLIMIT_CORE, &off) == 0
+    }
+}
+
+#[cfg(not(unix))]
+fn disable_core_dumps() -> bool {
+    false
+}
+
+#[cfg(target_os = "linux")]
+fn set_non_dumpable() -> bool {
+    // SAFETY: PR_SET_DUMPABLE takes plain integer arguments
+    unsafe { libc::prctl(libc::PR_SET_DUMPABLE, 0, 0, 0, 0) == 0 }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_non_dumpable() -> bool {
+    false
+}
+
+/// Whether `mlock` works at all here (it needs a non-zero `RLIMIT_MEMLOCK`).
+fn probe_memory_locking() -> bool {
+    let probe = Box::new([0u8; 32]);
+    if !lock(probe.as_ptr(), probe.len()) {
+        return false;
+    }
+    unlock(probe.as_ptr(), probe.len());
+    true
+}
+
+/// Restore the original core dump limit in a child before it execs.
+pub fn restore_for_child(cmd: &mut std::process::Command) {
+    #[cfg(unix)]
+    if let Some(limit) = *ORIGINAL_CORE_LIMIT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+    {
+        use std::os::unix::process::CommandExt;
+        // SAFETY: setrlimit is async-signal-safe and `limit` is a copy
+        unsafe {
+            cmd.pre_exec(move || {
+                libc::setrlimit(libc::RLIMIT_CORE, &limit);
+                Ok(())
+            });
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+#[cfg(unix)]
+fn page_size() -> usize {
+    // SAFETY: sysconf has no preconditions
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if size > 0 { size as usize } else { 4096 }
+}
+
+#[cfg(unix)]
+fn pages(ptr: *const u8, len: usize) -> impl Iterator<Item = usize> {
+    let size = page_size();
+    let start = ptr as usize & !(size - 1);
+    let end = ptr as usize + len;
+    (start..end).step_by(size)
+}
+
+/// Keep the pages under `ptr..ptr+len` out of swap; false if `mlock` failed.
+#[cfg(unix)]
+pub fn lock(ptr: *const u8, len: usize) -> bool {
+    if len == 0 {
+        return true;
+    }
+    let size = page_size();
+    let mut locked = LOCKED.lock().unwrap_or_else(|e| e.into_inner());
+    *locked.regions.entry((ptr as usize, len)).or_insert(0) += 1;
+    let mut ok = true;
+    for page in pages(ptr, len) {
+        let count = locked.pages.entry(page).or_insert(0);
+        // SAFETY: `page` is the start of a page that belongs to a live allocation
+        if *count == 0 && unsafe { libc::mlock(page as *const libc::c_void, size) } != 0 {
+            ok = false;
+        }
+        *count += 1;
+    }
+    if !ok {
+        LOCK_FAILED.store(true, Ordering::Relaxed);
+    }
+    ok
+}
+
+/// Release a region passed to `lock`; pages stay locked while other buffers use them.
+#[cfg(unix)]
+pub fn unlock(ptr: *const u8, len: usize) {
+    if len == 0 {
+        return;
+    }
+    let size = page_size();
+    let mut locked = LOCKED.lock().unwrap_or_else(|e| e.into_inner());
+    let region = (ptr as usize, len);
+    match locked.regions.get_mut(&region) {
+        Some(count) if *count > 1 => *count -= 1,
+        Some(_) => {
+            locked.regions.remove(&region);
+        }
+        None => return,
+    }
+    for page in pages(ptr, len) {
+        if let Some(count) = locked.pages.get_mut(&page) {
+            *count -= 1;
+            if *count == 0 {
+                locked.pages.remove(&page);
+                // SAFETY: unlocking a page we locked; failure is harmless
+                unsafe { libc::munlock(page as *const libc::c_void, size) };
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn lock(_ptr: *const u8, _len: usize) -> bool {
+    false
+}
+
+#[cfg(not(unix))]
+pub fn unlock(_ptr: *const u8, _len: usize) {}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_pages_stay_locked_until_last_release() {
+        // A page in the middle of a large buffer, so no other allocation shares it
+        let size = page_size();
+        let buf = vec![0u8; 3 * size];
+        let page = (buf.as_ptr() as usize + size) & !(size - 1);
+        let (a, b) = (page as *const u8, (page + 32) as *const u8);
+        let is_tracked = || LOCKED.lock().unwrap().pages.contains_key(&page);
+        lock(a, 32);
+        lock(b, 32);
+        unlock(a, 32);
+        // Releasing a region that was never locked changes nothing
+        unlock(a, 32);
+        assert!(is_tracked());
+        unlock(b, 32);
+        assert!(!is_tracked());
+    }
+}
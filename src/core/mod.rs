@@ -4,7 +4,11 @@
 //! - Cryptographic operations for secure data encryption/decryption
 //! - Configuration management for application settings and data persistence
 //! - Storage operations for managing encrypted credential sets
+//! - Secret-carrying types that wipe and redact their contents
+//! - Process hardening (no core dumps, no ptrace, locked memory)
 
 pub mod config;
 pub mod crypto;
+pub mod hardening;
+pub mod secret;
 pub mod store;
@@ -0,0 +1,250 @@
+//! Secret-carrying types: memory is overwritten on drop and never printed.
+//!
+//! Keys, passwords and decrypted credential maps go through these wrappers so
+//! they do not linger in freed memory, and so a stray `{:?}` in a log or error
+//! message cannot leak them. Keys and credential values are also kept out of
+//! swap (see `hardening`). Copies handed to other code (a child process
+//! environment, a GUI text buffer) are outside their protection.
+
+use crate::core::hardening;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A 256-bit encryption key, boxed so its locked memory never moves.
+pub struct SecretKey(Box<[u8; 32]>);
+
+impl SecretKey {
+    pub fn new(bytes: [u8; 32]) -> Self {
+        let key = SecretKey(Box::new(bytes));
+        hardening::lock(key.0.as_ptr(), key.0.len());
+        key
+    }
+
+    pub fn expose(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Writable buffer for filling the key in place (KDF output, file contents).
+    pub fn expose_mut(&mut self) -> &mut [u8; 32] {
+        &mut self.0
+    }
+}
+
+impl Clone for SecretKey {
+    fn clone(&self) -> Self {
+        SecretKey::new(*self.0)
+    }
+}
+
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+        hardening::unlock(self.0.as_ptr(), self.0.len());
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(<redacted>)")
+    }
+}
+
+/// A password or other secret text.
+#[derive(Clone, Default, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Editable text, for a GUI password field or reading in place. Growing
+    /// the string may leave earlier buffers behind.
+    pub fn expose_mut(&mut self) -> &mut String {
+        &mut self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(s: String) -> Self {
+        SecretString(s)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(s: &str) -> Self {
+        SecretString(s.to_string())
+    }
+}
+
+/// Serialized as plain text, for secrets stored inside encrypted files.
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(SecretString)
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString(<redacted>)")
+    }
+}
+
+/// Decrypted credentials, sorted by key; keys and values are wiped on drop.
+#[derive(Default, PartialEq, Eq)]
+pub struct SecretMap(BTreeMap<String, String>);
+
+impl SecretMap {
+    pub fn new() -> Self {
+        SecretMap::default()
+    }
+
+    /// Insert a value, keeping its memory out of swap while the map holds it.
+    /// A replaced value is handed back wrapped so it is wiped when dropped.
+    pub fn insert(&mut self, key: String, value: String) -> Option<SecretString> {
+        lock_value(&value);
+        self.0.insert(key, value).map(release)
+    }
+
+    /// Insert a value unless the key is already set, wiping the unused value.
+    pub fn insert_if_absent(&mut self, key: String, mut value: String) -> bool {
+        if self.0.contains_key(&key) {
+            value.zeroize();
+            return false;
+        }
+        self.insert(key, value);
+        true
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<SecretString> {
+        let (mut key, value) = self.0.remove_entry(key)?;
+        key.zeroize();
+        Some(release(value))
+    }
+
+    pub fn extend<I: IntoIterator<Item = (String, String)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl Clone for SecretMap {
+    fn clone(&self) -> Self {
+        self.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+fn lock_value(value: &String) {
+    hardening::lock(value.as_ptr(), value.capacity());
+}
+
+fn unlock_value(value: &String) {
+    hardening::unlock(value.as_ptr(), value.capacity());
+}
+
+/// A value leaving the map: unlocked, and wiped once the caller drops it.
+fn release(value: String) -> SecretString {
+    unlock_value(&value);
+    SecretString::from(value)
+}
+
+impl Deref for SecretMap {
+    type Target = BTreeMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for SecretMap {
+    fn drop(&mut self) {
+        for (mut key, mut value) in std::mem::take(&mut self.0) {
+            key.zeroize();
+            unlock_value(&value);
+            value.zeroize();
+        }
+    }
+}
+
+impl FromIterator<(String, String)> for SecretMap {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        let mut map = SecretMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a> IntoIterator for &'a SecretMap {
+    type Item = (&'a String, &'a String);
+    type IntoIter = std::collections::btree_map::Iter<'a, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Keys are shown, values are not.
+impl fmt::Debug for SecretMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.keys().map(|k| (k, "<redacted>")))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_output_is_redacted() {
+        let key = SecretKey::new([7; 32]);
+        let password = SecretString::from("hunter2"); // safehold:allow
+        let map: SecretMap = [("API_KEY".to_string(), "abc123".to_string())] // safehold:allow
+            .into_iter()
+            .collect();
+        assert_eq!(format!("{:?}", key), "SecretKey(<redacted>)");
+        assert_eq!(format!("{:?}", password), "SecretString(<redacted>)");
+        assert_eq!(format!("{:?}", map), r#"{"API_KEY": "<redacted>"}"#);
+    }
+
+    #[test]
+    fn map_mutators_hand_back_wrapped_values() {
+        let mut map = SecretMap::new();
+        assert!(map.insert("TOKEN".to_string(), "old".to_string()).is_none());
+        let replaced = map.insert("TOKEN".to_string(), "new".to_string());
+        assert_eq!(replaced.as_ref().map(SecretString::expose), Some("old"));
+        assert!(!map.insert_if_absent("TOKEN".to_string(), "other".to_string()));
+        assert!(map.insert_if_absent("PORT".to_string(), "8080".to_string()));
+        assert_eq!(map.get("TOKEN").map(String::as_str), Some("new"));
+        let removed = map.remove("PORT");
+        assert_eq!(removed.as_ref().map(SecretString::expose), Some("8080"));
+        assert!(map.remove("PORT").is_none());
+    }
+
+    #[test]
+    fn zeroize_wipes_contents() {
+        let mut key = SecretKey::new([7; 32]);
+        key.zeroize();
+        assert_eq!(key.expose(), &[0; 32]);
+        let mut password = SecretString::from("hunter2"); // safehold:allow
+        password.zeroize();
+        assert!(password.expose().is_empty());
+    }
+}
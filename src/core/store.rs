@@ -1,10 +1,17 @@
-//! Store-level commands: create/list/delete projects, setup, launch
-use crate::cli::cli::{CreateArgs, DeleteProjectArgs};
-use crate::cli::styles;
+//! Store-level commands: create/list/edit/rename/clone/archive/delete projects, setup, launch
+use crate::cli::cli::{
+    ArchiveArgs, CloneArgs, CreateArgs, DeleteProjectArgs, EditProjectArgs, ListProjectsArgs,
+    RenameArgs,
+};
+use crate::cli::{output, styles};
 use crate::core::config::{self, SetMeta};
 use crate::core::crypto;
+use crate::core::secret::{SecretKey, SecretString};
 use anyhow::{Context, Result, bail};
+use serde::Serialize;
 use std::fs;
+use std::path::Path;
+#[cfg(windows)]
 use std::process::Command;
 
 /// Create a new project (or configure global).
@@ -21,6 +28,11 @@ pub fn cmd_create(args: CreateArgs) -> Result<()> {
         config::next_set_id(&name, &cfg.sets)
     };
 
+    if id == "global"
+        && (args.description.is_some() || !args.tags.is_empty() || args.folder.is_some())
+    {
+        bail!("the global project has no description, tags or folder");
+    }
     let dir = if id == "global" {
         config::global_dir()?
     } else {
@@ -32,14 +44,11 @@ pub fn cmd_create(args: CreateArgs) -> Result<()> {
     fs::create_dir_all(&dir)?;
 
     if args.lock {
-        let password = match args.password {
+        let password = SecretString::from(match args.password {
             Some(p) => p,
-            None => {
-                let p = rpassword::prompt_password("Project password: ")?;
-                p
-            }
-        };
-        let lock = crypto::create_lock(&password)?;
+            None => rpassword::prompt_password("Project password: ")?,
+        });
+        let lock = crypto::create_lock(password.expose())?;
         fs::write(config::lock_path(&dir), serde_json::to_vec_pretty(&lock)?)?;
     }
 
@@ -48,6 +57,10 @@ pub fn cmd_create(args: CreateArgs) -> Result<()> {
             id: id.clone(),
             name: name.clone(),
             locked: args.lock,
+            archived: false,
+            description: clean_description(args.description.as_deref()),
+            tags: add_tags(Vec::new(), &args.tags)?,
+            folder: clean_folder(args.folder.as_deref()),
         };
         cfg.sets.push(meta);
         config::save_config(&cfg)?;
@@ -69,25 +82,89 @@ pub fn cmd_create(args: CreateArgs) -> Result<()> {
     Ok(())
 }
 
-/// List all projects (and global).
-pub fn cmd_list_sets() -> Result<()> {
-    let cfg = config::load_config()?;
+/// `list-projects --output json|yaml` document.
+#[derive(Serialize)]
+struct ProjectListDoc<'a> {
+    global: GlobalDoc,
+    projects: Vec<ProjectDoc<'a>>,
+    /// Archived projects left out because `--all` was not given
+    archived_hidden: usize,
+}
+
+#[derive(Serialize)]
+struct GlobalDoc {
+    locked: bool,
+}
+
+/// One project, with every field present even when unset.
+#[derive(Serialize)]
+struct ProjectDoc<'a> {
+    id: &'a str,
+    name: &'a str,
+    locked: bool,
+    archived: bool,
+    description: Option<&'a str>,
+    tags: &'a [String],
+    folder: Option<&'a str>,
+}
+
+impl<'a> From<&'a SetMeta> for ProjectDoc<'a> {
+    fn from(s: &'a SetMeta) -> Self {
+        ProjectDoc {
+            id: &s.id,
+            name: &s.name,
+            locked: s.locked,
+            archived: s.archived,
+            description: s.description.as_deref(),
+            tags: &s.tags,
+            folder: s.folder.as_deref(),
+        }
+    }
+}
+
+/// List all projects (and global); archived ones only with `--all`.
+pub fn cmd_list_sets(args: ListProjectsArgs) -> Result<()> {
+    let mut cfg = config::load_config()?;
+    let archived = cfg.sets.iter().filter(|s| s.archived).count();
+    if !args.all {
+        cfg.sets.retain(|s| !s.archived);
+    }
+    let filtered = args.tag.is_some() || args.folder.is_some();
+    if let Some(tag) = &args.tag {
+        cfg.sets
+            .retain(|s| s.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+    }
+    if let Some(folder) = &args.folder {
+        cfg.sets.retain(|s| s.in_folder(folder));
+    }
+
+    if output::is_structured() {
+        return output::emit(&ProjectListDoc {
+            global: GlobalDoc {
+                locked: cfg.global_locked,
+            },
+            projects: cfg.sets.iter().map(ProjectDoc::from).collect(),
+            archived_hidden: if args.all { 0 } else { archived },
+        });
+    }
 
     styles::header("SafeHold Projects");
     styles::divider();
 
-    if cfg.sets.is_empty() && !cfg.global_locked {
+    if cfg.sets.is_empty() && !cfg.global_locked && !filtered {
         styles::info("No projects found. Create one with: safehold create <name>");
         return Ok(());
     }
 
     // Show global project
-    let lock_status = if cfg.global_locked {
-        "🔒 Locked"
-    } else {
-        "🔓 Unlocked"
-    };
-    styles::kv("GLOBAL", format!("global - {}", lock_status));
+    if !filtered {
+        let lock_status = if cfg.global_locked {
+            "🔒 Locked"
+        } else {
+            "🔓 Unlocked"
+        };
+        styles::kv("GLOBAL", format!("global - {}", lock_status));
+    }
 
     // Show all custom projects
     let project_count = cfg.sets.len();
@@ -97,11 +174,311 @@ pub fn cmd_list_sets() -> Result<()> {
         } else {
             "🔓 Unlocked"
         };
-        styles::kv(&s.id, format!("{} - {}", s.name, lock_status));
+        let archived = if s.archived {
+            " - 🗄️ Archived"
+        } else {
+            ""
+        };
+        let folder = s
+            .folder
+            .as_deref()
+            .map(|f| format!(" - 📁 {}", f))
+            .unwrap_or_default();
+        let tags = if s.tags.is_empty() {
+            String::new()
+        } else {
+            format!(" - 🏷️ {}", s.tags.join(", "))
+        };
+        styles::kv(
+            &s.id,
+            format!("{} - {}{}{}{}", s.name, lock_status, archived, folder, tags),
+        );
+        if let Some(description) = &s.description {
+            styles::bullet(description);
+        }
     }
 
     println!();
-    styles::info(format!("Total: {} project(s) + global", project_count));
+    if filtered {
+        styles::info(format!("{} matching project(s)", project_count));
+    } else {
+        styles::info(format!("Total: {} project(s) + global", project_count));
+    }
+    if archived > 0 && !args.all {
+        styles::info(format!(
+            "{} archived project(s) hidden; show them with --all",
+            archived
+        ));
+    }
+    Ok(())
+}
+
+/// Index of the project with this id or name.
+fn find_set(cfg: &config::Config, id_or_name: &str) -> Result<usize> {
+    if id_or_name == "global" {
+        bail!("the global project cannot be renamed, cloned or archived");
+    }
+    cfg.sets
+        .iter()
+        .position(|s| s.id == id_or_name || s.name == id_or_name)
+        .with_context(|| format!("project not found: {}", id_or_name))
+}
+
+/// Reject names that are reserved or would make `-p <name>` ambiguous.
+fn check_new_name(cfg: &config::Config, name: &str, except: Option<usize>) -> Result<()> {
+    if name.trim().is_empty() || name == "global" {
+        bail!("'{}' cannot be used as a project name", name);
+    }
+    if let Some(other) = cfg
+        .sets
+        .iter()
+        .enumerate()
+        .find(|(i, s)| Some(*i) != except && (s.name == name || s.id == name))
+    {
+        bail!("a project named '{}' already exists ({})", name, other.1.id);
+    }
+    Ok(())
+}
+
+/// Rename a project, optionally moving its directory to a matching id.
+pub fn cmd_rename(args: RenameArgs) -> Result<()> {
+    let mut cfg = config::load_config()?;
+    let index = find_set(&cfg, &args.project)?;
+    check_new_name(&cfg, &args.new_name, Some(index))?;
+    let old = cfg.sets[index].clone();
+
+    let mut new_id = old.id.clone();
+    if args.rename_id {
+        new_id = match old.id.split_once('_') {
+            Some((n, _)) if n.parse::<u32>().is_ok() => format!("{}_{}", n, args.new_name),
+            _ => config::next_set_id(&args.new_name, &cfg.sets),
+        };
+        let (from, to) = (config::set_dir(&old.id)?, config::set_dir(&new_id)?);
+        if to.exists() {
+            bail!("project directory already exists: {}", new_id);
+        }
+        fs::rename(&from, &to)
+            .with_context(|| format!("move {} to {}", from.display(), to.display()))?;
+    }
+    cfg.sets[index].name = args.new_name.clone();
+    cfg.sets[index].id = new_id.clone();
+    config::save_config(&cfg)?;
+
+    styles::success(format!(
+        "✏️ Renamed project '{}' to '{}' (ID: {})",
+        old.name, args.new_name, new_id
+    ));
+    let old_refs = if old.id != new_id {
+        format!("'{}' or '{}'", old.name, old.id)
+    } else {
+        format!("'{}'", old.name)
+    };
+    styles::info(format!(
+        "Scripts and git hooks that refer to {} need updating",
+        old_refs
+    ));
+    Ok(())
+}
+
+/// Create a new project with a copy of another's credentials and settings.
+///
+/// The copy keeps the source's lock and password unless `--lock`, `--password`
+/// or `--no-lock` is given, in which case its encrypted files are re-encrypted.
+pub fn cmd_clone(args: CloneArgs) -> Result<()> {
+    let mut cfg = config::load_config()?;
+    let source = cfg.sets[find_set(&cfg, &args.project)?].clone();
+    check_new_name(&cfg, &args.name, None)?;
+    let relock = args.lock || args.password.is_some() || args.no_lock;
+    if relock && crate::operations::master_lock::is_master_lock_enabled() {
+        bail!(
+            "--lock/--no-lock have no effect while Global Master Lock is active; clone without them"
+        );
+    }
+    let src_dir = config::set_dir(&source.id)?;
+    // Unlock the source first so a wrong password leaves nothing behind
+    let old_key = if relock {
+        Some(crate::operations::envops::load_key_for_dir(&src_dir)?)
+    } else {
+        None
+    };
+
+    let id = config::next_set_id(&args.name, &cfg.sets);
+    let dir = config::set_dir(&id)?;
+    if dir.exists() {
+        bail!("project already exists: {}", id);
+    }
+    fs::create_dir_all(&dir)?;
+    let copied = copy_project_files(&src_dir, &dir, old_key.as_ref(), &args);
+    if let Err(e) = copied {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(e);
+    }
+
+    let locked = if relock {
+        !args.no_lock
+    } else {
+        config::lock_path(&dir).exists()
+    };
+    cfg.sets.push(SetMeta {
+        id: id.clone(),
+        name: args.name.clone(),
+        locked,
+        archived: false,
+        ..source.clone()
+    });
+    config::save_config(&cfg)?;
+    styles::success(format!(
+        "🧬 Cloned '{}' into '{}' ({}) with ID: {}",
+        source.name,
+        args.name,
+        if locked {
+            "🔒 locked"
+        } else {
+            "🔓 unlocked"
+        },
+        id
+    ));
+    Ok(())
+}
+
+/// Copy a project's files; with `old_key`, re-encrypt them under the clone's new lock.
+fn copy_project_files(
+    from: &Path,
+    to: &Path,
+    old_key: Option<&SecretKey>,
+    args: &CloneArgs,
+) -> Result<()> {
+    let new_key = match old_key {
+        None => None,
+        Some(_) if args.no_lock => Some(crypto::load_app_key(&config::base_dir()?)?),
+        Some(_) => {
+            let password = SecretString::from(match &args.password {
+                Some(p) => p.clone(),
+                None => rpassword::prompt_password(format!("Password for '{}': ", args.name))?,
+            });
+            let lock = crypto::create_lock(password.expose())?;
+            fs::write(config::lock_path(to), serde_json::to_vec_pretty(&lock)?)?;
+            Some(crypto::derive_key_from_password(password.expose(), &lock)?)
+        }
+    };
+    for entry in fs::read_dir(from).with_context(|| format!("read {}", from.display()))? {
+        let path = entry?.path();
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let target = to.join(name);
+        if !path.is_file() || (new_key.is_some() && path == config::lock_path(from)) {
+            continue;
+        }
+        match (old_key, &new_key) {
+            (Some(old), Some(new)) if path.extension().is_some_and(|e| e == "enc") => {
+                let plain = crypto::decrypt_with_key(old, &fs::read(&path)?)?;
+                fs::write(&target, crypto::encrypt_with_key(new, &plain)?)?;
+            }
+            _ => {
+                fs::copy(&path, &target).with_context(|| format!("copy {}", path.display()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Trimmed description; empty clears it.
+fn clean_description(description: Option<&str>) -> Option<String> {
+    description
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+}
+
+/// `clients//acme/` becomes `clients/acme`; empty clears the folder.
+fn clean_folder(folder: Option<&str>) -> Option<String> {
+    let path = folder?
+        .split('/')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    (!path.is_empty()).then_some(path)
+}
+
+/// Add tags (case-insensitively deduplicated), keeping the list sorted.
+fn add_tags(mut tags: Vec<String>, new: &[String]) -> Result<Vec<String>> {
+    for tag in new {
+        let tag = tag.trim();
+        if tag.is_empty() || tag.contains(char::is_whitespace) {
+            bail!("invalid tag '{}': tags are single words", tag);
+        }
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags.sort_by_key(|t| t.to_lowercase());
+    Ok(tags)
+}
+
+/// Show or change a project's description, tags and folder.
+pub fn cmd_edit_project(args: EditProjectArgs) -> Result<()> {
+    let mut cfg = config::load_config()?;
+    let index = find_set(&cfg, &args.project)?;
+    let set = &mut cfg.sets[index];
+    let changed = args.description.is_some()
+        || !args.tags.is_empty()
+        || !args.untags.is_empty()
+        || args.folder.is_some();
+    if args.description.is_some() {
+        set.description = clean_description(args.description.as_deref());
+    }
+    if args.folder.is_some() {
+        set.folder = clean_folder(args.folder.as_deref());
+    }
+    set.tags
+        .retain(|t| !args.untags.iter().any(|u| u.eq_ignore_ascii_case(t)));
+    set.tags = add_tags(std::mem::take(&mut set.tags), &args.tags)?;
+    let set = set.clone();
+    if changed {
+        config::save_config(&cfg)?;
+        styles::success(format!("📝 Updated project '{}'", set.name));
+    }
+
+    styles::header(format!("📁 {} ({})", set.name, set.id));
+    styles::kv(
+        "Description",
+        set.description.as_deref().unwrap_or("(none)"),
+    );
+    styles::kv("Folder", set.folder.as_deref().unwrap_or("(none)"));
+    styles::kv(
+        "Tags",
+        if set.tags.is_empty() {
+            "(none)".to_string()
+        } else {
+            set.tags.join(", ")
+        },
+    );
+    Ok(())
+}
+
+/// Hide or show a project in listings; returns its display name.
+pub fn set_archived(id_or_name: &str, archived: bool) -> Result<String> {
+    let mut cfg = config::load_config()?;
+    let index = find_set(&cfg, id_or_name)?;
+    cfg.sets[index].archived = archived;
+    config::save_config(&cfg)?;
+    Ok(cfg.sets[index].name.clone())
+}
+
+/// Archive or unarchive a project.
+pub fn cmd_archive(args: ArchiveArgs, archived: bool) -> Result<()> {
+    let name = set_archived(&args.project, archived)?;
+    if archived {
+        styles::success(format!(
+            "🗄️ Archived project '{}'; it is hidden from list-projects, show-all and the GUI",
+            name
+        ));
+        styles::info("It still works with -p; restore it with `safehold unarchive`");
+    } else {
+        styles::success(format!("📂 Unarchived project '{}'", name));
+    }
     Ok(())
 }
 
@@ -9,20 +9,64 @@ use std::collections::{BTreeMap, HashMap};
 #[cfg(feature = "gui")]
 use std::fs;
 #[cfg(feature = "gui")]
-use std::path::PathBuf;
+use std::path::Path;
 #[cfg(feature = "gui")]
 use std::time::{Duration, Instant};
+#[cfg(feature = "gui")]
+use zeroize::{Zeroize, Zeroizing};
 
 #[cfg(feature = "gui")]
 use crate::cli::cli::CreateArgs;
 use crate::cli::cli::{ExportArgs, RunArgs};
 #[cfg(feature = "gui")]
-use crate::core::config::{self, Config, env_enc_path, lock_path};
+use crate::core::config::{self, Config, SetMeta, env_enc_path, lock_path};
 #[cfg(feature = "gui")]
 use crate::core::crypto::{self, LockInfo};
 #[cfg(feature = "gui")]
+use crate::core::secret::{SecretKey, SecretMap, SecretString};
+#[cfg(feature = "gui")]
 use crate::core::store::{self};
 use crate::operations::envops;
+#[cfg(feature = "gui")]
+use crate::operations::expiry::{self, DueKey};
+#[cfg(feature = "gui")]
+use crate::operations::generate;
+#[cfg(feature = "gui")]
+use crate::operations::otp::{self, Totp};
+#[cfg(feature = "gui")]
+use crate::operations::typed::{self, TypedEntry};
+
+/// Projects grouped by their folder path for the Projects tab tree.
+#[cfg(feature = "gui")]
+#[derive(Default)]
+struct FolderNode {
+    children: BTreeMap<String, FolderNode>,
+    projects: Vec<SetMeta>,
+}
+
+#[cfg(feature = "gui")]
+impl FolderNode {
+    fn insert(&mut self, project: &SetMeta) {
+        let mut node = self;
+        for part in project.folder.iter().flat_map(|f| f.split('/')) {
+            node = node.children.entry(part.to_string()).or_default();
+        }
+        node.projects.push(project.clone());
+    }
+
+    fn project_count(&self) -> usize {
+        self.projects.len()
+            + self
+                .children
+                .values()
+                .map(FolderNode::project_count)
+                .sum::<usize>()
+    }
+}
+
+/// How far ahead the Statistics tab looks for expiring credentials.
+#[cfg(feature = "gui")]
+const EXPIRY_PANEL_WINDOW: Duration = Duration::from_secs(30 * 24 * 60 * 60);
 
 #[cfg(feature = "gui")]
 #[derive(Debug, Clone, PartialEq)]
@@ -56,12 +100,13 @@ enum NotificationSeverity {
 struct SafeHoldApp {
     cfg: Config,
     selected: Option<String>,
-    maps_cache: HashMap<String, BTreeMap<String, String>>,
-    passwords: HashMap<String, String>,
+    maps_cache: HashMap<String, SecretMap>,
+    passwords: HashMap<String, SecretString>,
 
     // UI State
     current_tab: TabView,
     search_filter: String,
+    show_archived: bool,
     show_passwords: bool,
     auto_save_interval: u64,
 
@@ -69,6 +114,8 @@ struct SafeHoldApp {
     show_create: bool,
     new_project_name: String,
     new_project_lock: bool,
+    new_project_folder: String,
+    new_project_tags: String,
     new_project_password: String,
     new_project_confirm_password: String,
 
@@ -76,6 +123,7 @@ struct SafeHoldApp {
     show_add_credential: bool,
     new_key: String,
     new_val: String,
+    new_val_generated: bool,
 
     // Export dialog
     show_export: bool,
@@ -109,7 +157,7 @@ struct SafeHoldApp {
     delete_project: String, // for credential deletion
 
     // Global credentials
-    global_credentials: BTreeMap<String, String>,
+    global_credentials: SecretMap,
 
     // Global operations dialogs
     show_add_global: bool,
@@ -126,11 +174,16 @@ struct SafeHoldApp {
 
     // Notifications
     notifications: Vec<NotificationMessage>,
+    /// When to clear a copied value, and its digest
+    clipboard_clear: Option<(Instant, String)>,
+    /// Last user input, for locking the session after `session_timeout_minutes`
+    last_activity: Instant,
 
     // Statistics
     total_projects: usize,
     total_credentials: usize,
     duplicate_keys: Vec<(String, Vec<String>)>,
+    expiring: Vec<DueKey>,
 
     // Master Lock functionality
     master_lock_enabled: bool,
@@ -140,7 +193,6 @@ struct SafeHoldApp {
     master_password_confirm: String,
 
     // App Settings
-    #[allow(dead_code)]
     app_settings: crate::utils::app_settings::AppSettings,
 
     // Update checking
@@ -154,23 +206,27 @@ struct SafeHoldApp {
 impl SafeHoldApp {
     fn new() -> Self {
         let cfg = config::load_config().unwrap_or_default();
-        Self {
+        let mut app = Self {
             cfg,
             selected: None,
             maps_cache: HashMap::new(),
             passwords: HashMap::new(),
             current_tab: TabView::Projects,
             search_filter: String::new(),
+            show_archived: false,
             show_passwords: false,
             auto_save_interval: 30,
             show_create: false,
             new_project_name: String::new(),
             new_project_lock: false,
+            new_project_folder: String::new(),
+            new_project_tags: String::new(),
             new_project_password: String::new(),
             new_project_confirm_password: String::new(),
             show_add_credential: false,
             new_key: String::new(),
             new_val: String::new(),
+            new_val_generated: false,
             show_export: false,
             export_project: String::new(),
             export_format: "env".to_string(),
@@ -194,7 +250,7 @@ impl SafeHoldApp {
             show_warning_dialog: false,
             warning_title: String::new(),
             warning_message: String::new(),
-            global_credentials: BTreeMap::new(),
+            global_credentials: SecretMap::new(),
             show_add_global: false,
             show_update_global: false,
             global_new_key: String::new(),
@@ -205,9 +261,12 @@ impl SafeHoldApp {
             show_delete_all_confirm: false,
             show_about_dialog: false,
             notifications: Vec::new(),
+            clipboard_clear: None,
+            last_activity: Instant::now(),
             total_projects: 0,
             total_credentials: 0,
             duplicate_keys: Vec::new(),
+            expiring: Vec::new(),
             master_lock_enabled: crate::operations::master_lock::is_master_lock_enabled(),
             show_master_lock_dialog: false,
             master_lock_action: None,
@@ -218,7 +277,9 @@ impl SafeHoldApp {
             update_info: None,
             last_update_check: None,
             update_check_in_progress: false,
-        }
+        };
+        app.refresh_expiring();
+        app
     }
 
     fn add_notification(&mut self, text: String, severity: NotificationSeverity) {
@@ -244,6 +305,104 @@ impl SafeHoldApp {
         }
     }
 
+    /// Copy a secret, clearing it after `clipboard_clear_seconds` if it is still there.
+    fn copy_secret(&mut self, text: &str, message: String) {
+        if let Err(e) = crate::utils::clipboard::set_text(text) {
+            self.add_notification(format!("Copy failed: {:#}", e), NotificationSeverity::Error);
+            return;
+        }
+        let delay = self.app_settings.security.clipboard_clear_seconds;
+        self.clipboard_clear = (delay > 0).then(|| {
+            (
+                Instant::now() + Duration::from_secs(delay.into()),
+                crate::utils::clipboard::digest(text),
+            )
+        });
+        self.add_notification(
+            format!("{}{}", message, crate::utils::clipboard::clear_note(delay)),
+            NotificationSeverity::Info,
+        );
+    }
+
+    /// Clear the clipboard once a copied value's time is up.
+    fn check_clipboard_clear(&mut self, ctx: &egui::Context) {
+        let Some((deadline, _)) = &self.clipboard_clear else {
+            return;
+        };
+        let now = Instant::now();
+        if now < *deadline {
+            ctx.request_repaint_after(*deadline - now);
+            return;
+        }
+        if let Some((_, digest)) = self.clipboard_clear.take()
+            && let Ok(true) = crate::utils::clipboard::clear_if_unchanged(&digest)
+        {
+            self.add_notification("Clipboard cleared".to_string(), NotificationSeverity::Info);
+        }
+    }
+
+    /// Wipe every decrypted value and typed password; locked projects then need
+    /// their password again.
+    fn lock_session(&mut self) {
+        // Dropping the secret types overwrites them
+        self.maps_cache.clear();
+        self.passwords.clear();
+        self.global_credentials = SecretMap::new();
+        for field in [
+            &mut self.new_val,
+            &mut self.update_value,
+            &mut self.global_new_value,
+            &mut self.global_update_value,
+            &mut self.new_project_password,
+            &mut self.new_project_confirm_password,
+            &mut self.master_password_input,
+            &mut self.master_password_confirm,
+        ] {
+            field.zeroize();
+        }
+        self.show_add_credential = false;
+        self.show_update_credential = false;
+        self.show_add_global = false;
+        self.show_update_global = false;
+        self.show_master_lock_dialog = false;
+        // Projects without a password reopen straight away
+        if let Some(selected) = self.selected.clone()
+            && !self.is_locked(&selected)
+        {
+            let _ = self.ensure_loaded(&selected);
+        }
+        self.refresh_expiring();
+        self.last_activity = Instant::now();
+    }
+
+    /// Lock the session once the user has been idle for `session_timeout_minutes`.
+    fn check_session_timeout(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| !i.events.is_empty()) {
+            self.last_activity = Instant::now();
+        }
+        let minutes = self.app_settings.security.session_timeout_minutes;
+        if minutes == 0 {
+            return;
+        }
+        let limit = Duration::from_secs(u64::from(minutes) * 60);
+        let idle = self.last_activity.elapsed();
+        if idle < limit {
+            ctx.request_repaint_after(limit - idle);
+            return;
+        }
+        if !self.passwords.is_empty() || !self.maps_cache.is_empty() {
+            self.lock_session();
+            self.add_notification(
+                format!(
+                    "🔒 Session locked after {} minute(s) of inactivity",
+                    minutes
+                ),
+                NotificationSeverity::Info,
+            );
+        }
+        self.last_activity = Instant::now();
+    }
+
     fn show_error_dialog(&mut self, message: &str) {
         self.error_title = "Error".to_string();
         self.error_message = message.to_string();
@@ -271,10 +430,12 @@ impl SafeHoldApp {
         } else {
             config::set_dir(id_or_global)?
         };
-        let pwd = self.passwords.get(id_or_global).map(|s| s.as_str());
+        let pwd = self.passwords.get(id_or_global).map(|s| s.expose());
         match read_env_map_dir(&dir, pwd) {
             Ok(map) => {
                 self.maps_cache.insert(id_or_global.to_string(), map);
+                // A newly unlocked project may have keys coming due
+                self.refresh_expiring();
                 Ok(())
             }
             Err(e) => {
@@ -308,6 +469,11 @@ impl SafeHoldApp {
             return;
         }
 
+        if let Err(e) = typed::validate_value(&self.new_val) {
+            self.add_notification(e.to_string(), NotificationSeverity::Warning);
+            return;
+        }
+
         let map = self.maps_cache.entry(id_or_global.to_string()).or_default();
         if map.contains_key(&self.new_key) {
             self.add_notification(
@@ -337,7 +503,7 @@ impl SafeHoldApp {
         } else {
             config::set_dir(id_or_global)?
         };
-        let pwd = self.passwords.get(id_or_global).map(|s| s.as_str());
+        let pwd = self.passwords.get(id_or_global).map(|s| s.expose());
         if let Some(map) = self.maps_cache.get(id_or_global) {
             write_env_map_dir(&dir, map, pwd)
         } else {
@@ -364,11 +530,12 @@ impl SafeHoldApp {
         if let Ok(cfg) = config::load_config() {
             self.cfg = cfg;
             self.update_statistics();
+            self.refresh_expiring();
         }
     }
 
     fn update_statistics(&mut self) {
-        self.total_projects = self.cfg.sets.len();
+        self.total_projects = self.cfg.sets.iter().filter(|s| !s.archived).count();
         self.total_credentials = self.maps_cache.values().map(|m| m.len()).sum();
 
         // Find duplicate keys across projects
@@ -403,6 +570,35 @@ impl SafeHoldApp {
             .collect();
     }
 
+    /// Recompute the expiry panel from the projects the GUI can open: those
+    /// without a password and locked ones unlocked this session. Settings are
+    /// encrypted with each project key, so this runs on refresh, not per frame.
+    fn refresh_expiring(&mut self) {
+        let limit = time::OffsetDateTime::now_utc() + EXPIRY_PANEL_WINDOW;
+        let mut projects = vec![("global".to_string(), "global".to_string())];
+        projects.extend(self.cfg.sets.iter().map(|s| (s.id.clone(), s.name.clone())));
+        let mut due = Vec::new();
+        for (id, name) in projects {
+            if self.is_locked(&id) && !self.maps_cache.contains_key(&id) {
+                continue;
+            }
+            let dir = if id == "global" {
+                config::global_dir()
+            } else {
+                config::set_dir(&id)
+            };
+            let pwd = self.passwords.get(&id).map(|s| s.expose());
+            if let Ok(dir) = dir
+                && let Ok(key) = load_key_for_dir_gui(&dir, pwd)
+                && let Ok(keys) = expiry::due_in_project(&name, &dir, &key, limit)
+            {
+                due.extend(keys);
+            }
+        }
+        due.sort_by_key(|d| d.due);
+        self.expiring = due;
+    }
+
     fn setup_style(&self, ctx: &egui::Context) {
         let mut style = (*ctx.style()).clone();
 
@@ -424,6 +620,24 @@ impl SafeHoldApp {
         ctx.set_style(style);
     }
 
+    fn set_archived(&mut self, id: &str, archived: bool) {
+        match store::set_archived(id, archived) {
+            Ok(name) => {
+                let text = if archived {
+                    format!("🗄️ Archived project '{}'", name)
+                } else {
+                    format!("📂 Unarchived project '{}'", name)
+                };
+                self.add_notification(text, NotificationSeverity::Success);
+                self.refresh_config();
+            }
+            Err(e) => self.add_notification(
+                format!("Failed to update project: {}", e),
+                NotificationSeverity::Error,
+            ),
+        }
+    }
+
     fn lock_unlock_global(&mut self, lock: bool) {
         match (lock, self.is_locked("global")) {
             (true, false) => {
@@ -454,7 +668,7 @@ impl SafeHoldApp {
                                     } else {
                                         self.passwords.insert(
                                             "global".into(),
-                                            self.new_project_password.clone(),
+                                            self.new_project_password.as_str().into(),
                                         );
                                         self.add_notification(
                                             "Global project locked".to_string(),
@@ -737,6 +951,8 @@ impl App for SafeHoldApp {
         // Clean old notifications
         self.notifications
             .retain(|n| n.timestamp.elapsed() < Duration::from_secs(5));
+        self.check_clipboard_clear(ctx);
+        self.check_session_timeout(ctx);
 
         // Top navigation bar
         egui::TopBottomPanel::top("top_nav").show(ctx, |ui| {
@@ -774,6 +990,18 @@ impl App for SafeHoldApp {
                 tab_button(ui, TabView::Statistics, "Statistics", "📊");
 
                 ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui
+                        .button("🔒 Lock now")
+                        .on_hover_text("Forget decrypted values and passwords")
+                        .clicked()
+                    {
+                        self.lock_session();
+                        self.add_notification(
+                            "🔒 Session locked".to_string(),
+                            NotificationSeverity::Info,
+                        );
+                    }
+
                     // Quick actions
                     if ui.button("➕ New Project").clicked() {
                         self.show_create = true;
@@ -916,7 +1144,7 @@ impl SafeHoldApp {
                         ui.label("🔒 Locked - Enter password to access:");
                         let pw = self.passwords.entry("global".into()).or_default();
                         if ui
-                            .add(egui::TextEdit::singleline(pw).password(true))
+                            .add(egui::TextEdit::singleline(pw.expose_mut()).password(true))
                             .changed()
                         {
                             self.maps_cache.remove("global");
@@ -934,7 +1162,16 @@ impl SafeHoldApp {
             ui.separator();
 
             // Project list
-            ui.heading("📂 User Projects");
+            ui.horizontal(|ui| {
+                ui.heading("📂 User Projects");
+                let archived = self.cfg.sets.iter().filter(|s| s.archived).count();
+                if archived > 0 {
+                    ui.checkbox(
+                        &mut self.show_archived,
+                        format!("🗄️ Show archived ({})", archived),
+                    );
+                }
+            });
 
             egui::ScrollArea::vertical().show(ui, |ui| {
                 if self.cfg.sets.is_empty() {
@@ -946,69 +1183,111 @@ impl SafeHoldApp {
                         );
                     });
                 } else {
-                    for project in &self.cfg.sets.clone() {
-                        if !self.search_filter.is_empty()
-                            && !project
-                                .name
-                                .to_lowercase()
-                                .contains(&self.search_filter.to_lowercase())
-                        {
+                    let filter = self.search_filter.to_lowercase();
+                    let mut tree = FolderNode::default();
+                    for project in &self.cfg.sets {
+                        if project.archived && !self.show_archived {
+                            continue;
+                        }
+                        let haystack = format!(
+                            "{} {} {} {}",
+                            project.name,
+                            project.description.as_deref().unwrap_or_default(),
+                            project.folder.as_deref().unwrap_or_default(),
+                            project.tags.join(" ")
+                        );
+                        if !filter.is_empty() && !haystack.to_lowercase().contains(&filter) {
                             continue;
                         }
+                        tree.insert(project);
+                    }
+                    self.render_folder(ui, &tree, "");
+                }
+            });
+        });
+    }
 
-                        ui.group(|ui| {
-                            let is_locked = self.is_locked(&project.id);
-                            ui.horizontal(|ui| {
-                                let lock_icon = if is_locked { "🔒" } else { "🔓" };
+    /// Folders as collapsible sections, then the projects directly inside.
+    fn render_folder(&mut self, ui: &mut Ui, node: &FolderNode, path: &str) {
+        for (name, child) in &node.children {
+            let child_path = format!("{}/{}", path, name);
+            egui::CollapsingHeader::new(
+                RichText::new(format!("📁 {} ({})", name, child.project_count())).size(15.0),
+            )
+            .id_salt(&child_path)
+            .default_open(true)
+            .show(ui, |ui| self.render_folder(ui, child, &child_path));
+        }
+        for project in &node.projects {
+            self.render_project_row(ui, project);
+        }
+    }
 
-                                ui.label(
-                                    RichText::new(format!("{} {}", lock_icon, project.name))
-                                        .size(16.0),
-                                );
+    fn render_project_row(&mut self, ui: &mut Ui, project: &SetMeta) {
+        ui.group(|ui| {
+            let is_locked = self.is_locked(&project.id);
+            ui.horizontal(|ui| {
+                let lock_icon = if is_locked { "🔒" } else { "🔓" };
 
-                                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                                    if ui.button("❌ Delete").clicked() {
-                                        self.delete_type = "project".to_string();
-                                        self.delete_target = project.id.clone();
-                                        self.delete_project = project.name.clone();
-                                        self.show_delete_confirm = true;
-                                    }
+                ui.label(RichText::new(format!("{} {}", lock_icon, project.name)).size(16.0));
+                if project.archived {
+                    ui.label(RichText::new("🗄️ Archived").color(Color32::GRAY));
+                }
+                for tag in &project.tags {
+                    ui.label(RichText::new(format!("🏷️ {}", tag)).small());
+                }
 
-                                    if ui.button("📋 View Credentials").clicked() {
-                                        self.select(&project.id);
-                                    }
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui.button("❌ Delete").clicked() {
+                        self.delete_type = "project".to_string();
+                        self.delete_target = project.id.clone();
+                        self.delete_project = project.name.clone();
+                        self.show_delete_confirm = true;
+                    }
 
-                                    if ui.button("📤 Export").clicked() {
-                                        self.export_project = project.id.clone();
-                                        self.show_export = true;
-                                    }
+                    let (label, archive) = if project.archived {
+                        ("📂 Unarchive", false)
+                    } else {
+                        ("🗄️ Archive", true)
+                    };
+                    if ui.button(label).clicked() {
+                        self.set_archived(&project.id, archive);
+                    }
 
-                                    if ui.button("▶️ Run Command").clicked() {
-                                        self.run_project = project.id.clone();
-                                        self.show_run_command = true;
-                                    }
-                                });
-                            });
+                    if ui.button("📋 View Credentials").clicked() {
+                        self.select(&project.id);
+                    }
 
-                            if is_locked {
-                                ui.horizontal(|ui| {
-                                    ui.label("Password:");
-                                    let pw = self.passwords.entry(project.id.clone()).or_default();
-                                    ui.add(egui::TextEdit::singleline(pw).password(true));
-                                    if ui.button("Unlock").clicked() {
-                                        self.maps_cache.remove(&project.id);
-                                        let _ = self.ensure_loaded(&project.id);
-                                    }
-                                });
-                            }
+                    if ui.button("📤 Export").clicked() {
+                        self.export_project = project.id.clone();
+                        self.show_export = true;
+                    }
 
-                            ui.label(format!("ID: {}", project.id));
-                        });
-                        ui.add_space(5.0);
+                    if ui.button("▶️ Run Command").clicked() {
+                        self.run_project = project.id.clone();
+                        self.show_run_command = true;
                     }
-                }
+                });
             });
+
+            if is_locked {
+                ui.horizontal(|ui| {
+                    ui.label("Password:");
+                    let pw = self.passwords.entry(project.id.clone()).or_default();
+                    ui.add(egui::TextEdit::singleline(pw.expose_mut()).password(true));
+                    if ui.button("Unlock").clicked() {
+                        self.maps_cache.remove(&project.id);
+                        let _ = self.ensure_loaded(&project.id);
+                    }
+                });
+            }
+
+            if let Some(description) = &project.description {
+                ui.label(RichText::new(description).italics());
+            }
+            ui.label(format!("ID: {}", project.id));
         });
+        ui.add_space(5.0);
     }
 
     fn render_credentials_tab(&mut self, ui: &mut Ui) {
@@ -1057,7 +1336,7 @@ impl SafeHoldApp {
                                 ui.label("Enter password to view credentials:");
 
                                 let pw = self.passwords.entry(selected.clone()).or_default();
-                                ui.add(egui::TextEdit::singleline(pw)
+                                ui.add(egui::TextEdit::singleline(pw.expose_mut())
                                     .password(true)
                                     .hint_text("Enter project password"));
 
@@ -1093,21 +1372,86 @@ impl SafeHoldApp {
                                     ui.label(RichText::new("Actions").strong());
                                     ui.end_row();
 
-                                    let mut entries: Vec<(String, String)> = map.into_iter().collect();
-                                    entries.sort_by(|a, b| a.0.cmp(&b.0));
-
-                                    for (key, value) in entries {
+                                    // Borrowed from the (wiped-on-drop) copy, already sorted by key
+                                    for (key, value) in map.iter() {
                                         if !self.search_filter.is_empty() &&
                                            !key.to_lowercase().contains(&self.search_filter.to_lowercase()) &&
-                                           !value.to_lowercase().contains(&self.search_filter.to_lowercase()) {
+                                           !Zeroizing::new(value.to_lowercase()).contains(&self.search_filter.to_lowercase()) {
                                             continue;
                                         }
 
                                         // Key column
-                                        ui.label(RichText::new(&key).monospace());
+                                        ui.label(RichText::new(key).monospace());
+
+                                        // One-time password credentials show the live code instead
+                                        if let Some(totp) = otp::is_otpauth(value)
+                                            .then(|| Totp::from_uri(value).ok())
+                                            .flatten()
+                                        {
+                                            let (code, remaining) = totp.now();
+                                            ui.horizontal(|ui| {
+                                                ui.label(RichText::new(&code).monospace().strong().size(16.0));
+                                                ui.add(
+                                                    egui::ProgressBar::new(remaining as f32 / totp.period() as f32)
+                                                        .desired_width(80.0)
+                                                        .text(format!("{}s", remaining)),
+                                                );
+                                            })
+                                            .response
+                                            .on_hover_text(if totp.label.is_empty() { "One-time password" } else { totp.label.as_str() });
+                                            ui.label("⏱️ TOTP");
+                                            ui.horizontal(|ui| {
+                                                if ui.small_button("📋").on_hover_text("Copy current code").clicked() {
+                                                    self.copy_secret(&code, format!("Copied one-time code for '{}'", key));
+                                                }
+                                                if ui.small_button("❌").on_hover_text("Delete").clicked() {
+                                                    self.delete_key(selected, key);
+                                                }
+                                            });
+                                            ui.end_row();
+                                            ui.ctx().request_repaint_after(Duration::from_secs(1));
+                                            continue;
+                                        }
+
+                                        // Typed credentials show their fields grouped under the key
+                                        if let Some(Ok(entry)) = TypedEntry::parse(value) {
+                                            let fields = entry.display_fields();
+                                            ui.collapsing(format!("{} field(s)", fields.len()), |ui| {
+                                                egui::Grid::new(format!("typed_fields_{}", key))
+                                                    .num_columns(2)
+                                                    .spacing([8.0, 2.0])
+                                                    .show(ui, |ui| {
+                                                        for (name, field_value, secret) in fields {
+                                                            ui.label(RichText::new(name).color(Color32::GRAY));
+                                                            if secret && !self.show_passwords {
+                                                                ui.monospace("•".repeat(field_value.len().min(12)));
+                                                            } else {
+                                                                ui.monospace(field_value);
+                                                            }
+                                                            ui.end_row();
+                                                        }
+                                                    });
+                                            });
+                                            ui.label(format!("🧩 {}", entry.kind.name()));
+                                            ui.horizontal(|ui| {
+                                                if ui.small_button("📋").on_hover_text("Copy as env vars").clicked() {
+                                                    let lines: Vec<String> = entry
+                                                        .env_vars(key)
+                                                        .into_iter()
+                                                        .map(|(name, v)| format!("{}={}", name, v))
+                                                        .collect();
+                                                    self.copy_secret(&lines.join("\n"), format!("Copied '{}' as env vars", key));
+                                                }
+                                                if ui.small_button("❌").on_hover_text("Delete").clicked() {
+                                                    self.delete_key(selected, key);
+                                                }
+                                            });
+                                            ui.end_row();
+                                            continue;
+                                        }
 
                                         // Value column
-                                        let mut display_value = if self.show_passwords {
+                                        let mut display_value = Zeroizing::new(if self.show_passwords {
                                             value.clone()
                                         } else {
                                             if key.to_lowercase().contains("password") ||
@@ -1118,16 +1462,16 @@ impl SafeHoldApp {
                                             } else {
                                                 value.clone()
                                             }
-                                        };
+                                        });
 
                                         let value_response = ui.add(
-                                            egui::TextEdit::singleline(&mut display_value)
+                                            egui::TextEdit::singleline(&mut *display_value)
                                                 .desired_width(200.0)
                                         );
 
                                         if value_response.changed() && self.show_passwords {
                                             if let Some(m) = self.maps_cache.get_mut(selected) {
-                                                m.insert(key.clone(), display_value);
+                                                m.insert(key.clone(), std::mem::take(&mut *display_value));
                                             }
                                         }
 
@@ -1147,11 +1491,10 @@ impl SafeHoldApp {
                                         // Actions
                                         ui.horizontal(|ui| {
                                             if ui.small_button("📋").on_hover_text("Copy").clicked() {
-                                                ui.ctx().copy_text(value.clone());
-                                                self.add_notification(format!("Copied '{}'", key), NotificationSeverity::Info);
+                                                self.copy_secret(value, format!("Copied '{}'", key));
                                             }
                                             if ui.small_button("❌").on_hover_text("Delete").clicked() {
-                                                self.delete_key(selected, &key);
+                                                self.delete_key(selected, key);
                                             }
                                         });
 
@@ -1198,6 +1541,9 @@ impl SafeHoldApp {
 
             egui::ScrollArea::vertical().show(ui, |ui| {
                 for project in &self.cfg.sets.clone() {
+                    if project.archived {
+                        continue;
+                    }
                     ui.group(|ui| {
                         ui.horizontal(|ui| {
                             ui.label(RichText::new(&project.name).size(16.0));
@@ -1245,6 +1591,18 @@ impl SafeHoldApp {
                     ui.label("Auto-save interval (seconds):");
                     ui.add(egui::DragValue::new(&mut self.auto_save_interval).range(5..=300));
                 });
+                ui.horizontal(|ui| {
+                    ui.label("Lock after inactivity (minutes, 0 = never):");
+                    let timeout = &mut self.app_settings.security.session_timeout_minutes;
+                    if ui.add(egui::DragValue::new(timeout).range(0..=1440)).changed() {
+                        let minutes = *timeout;
+                        if let Err(e) = crate::utils::app_settings::update_security_settings(|s| {
+                            s.session_timeout_minutes = minutes
+                        }) {
+                            self.add_notification(format!("Failed to save settings: {}", e), NotificationSeverity::Error);
+                        }
+                    }
+                });
             });
 
             ui.separator();
@@ -1439,7 +1797,9 @@ impl SafeHoldApp {
                         .clicked()
                     {
                         // Add global credential
-                        if let Ok(global_dir) = config::global_dir() {
+                        if let Err(e) = typed::validate_value(&self.global_new_value) {
+                            self.add_notification(e.to_string(), NotificationSeverity::Warning);
+                        } else if let Ok(global_dir) = config::global_dir() {
                             let mut map = self.global_credentials.clone();
                             map.insert(self.global_new_key.clone(), self.global_new_value.clone());
                             if let Err(e) = self.write_env_map(&global_dir, &map) {
@@ -1518,10 +1878,9 @@ impl SafeHoldApp {
                                         self.show_update_global = true;
                                     }
                                     if ui.button("📋 Copy").clicked() {
-                                        ui.ctx().copy_text(value.clone());
-                                        self.add_notification(
+                                        self.copy_secret(
+                                            value,
                                             "Value copied to clipboard".to_string(),
-                                            NotificationSeverity::Info,
                                         );
                                     }
                                 });
@@ -1574,11 +1933,61 @@ impl SafeHoldApp {
                         );
                         ui.label("Duplicate Keys");
                     });
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new(format!("{}", self.expiring.len()))
+                                .size(24.0)
+                                .color(Color32::from_rgb(200, 60, 60)),
+                        );
+                        ui.label("Expiring Soon");
+                    });
                 });
             });
 
             ui.separator();
 
+            // Expiry and rotation panel
+            ui.group(|ui| {
+                ui.label(RichText::new("Expiry & Rotation").strong().size(16.0));
+                if self.expiring.is_empty() {
+                    ui.label(
+                        RichText::new("No credentials expire or need rotation in the next 30 days")
+                            .color(Color32::GRAY),
+                    );
+                } else {
+                    let now = time::OffsetDateTime::now_utc();
+                    egui::ScrollArea::vertical()
+                        .id_salt("expiry_panel")
+                        .max_height(150.0)
+                        .show(ui, |ui| {
+                            for due in &self.expiring {
+                                let color = if due.due <= now {
+                                    Color32::from_rgb(200, 0, 0)
+                                } else {
+                                    Color32::from_rgb(200, 150, 0)
+                                };
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new(&due.project).strong());
+                                    ui.label(RichText::new(&due.key).monospace());
+                                    ui.label("→");
+                                    ui.label(
+                                        RichText::new(expiry::describe_due(due.due, due.reason, now))
+                                            .color(color),
+                                    );
+                                });
+                            }
+                        });
+                }
+                ui.label(
+                    RichText::new("Set dates with `safehold expiry -p <project> -k <key> --expires-at 2026-12-31 --rotate-every 90d`")
+                        .small()
+                        .color(Color32::GRAY),
+                );
+            });
+
+            ui.separator();
+
             // Duplicate keys analysis
             if !self.duplicate_keys.is_empty() {
                 ui.group(|ui| {
@@ -1636,6 +2045,20 @@ impl SafeHoldApp {
                         ui.label("📂 Project Name:");
                         ui.text_edit_singleline(&mut self.new_project_name);
                     });
+                    ui.horizontal(|ui| {
+                        ui.label("📁 Folder:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.new_project_folder)
+                                .hint_text("optional, e.g. clients/acme"),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("🏷️ Tags:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.new_project_tags)
+                                .hint_text("optional, comma-separated"),
+                        );
+                    });
 
                     ui.separator();
 
@@ -1679,6 +2102,16 @@ impl SafeHoldApp {
                                 } else {
                                     None
                                 },
+                                description: None,
+                                tags: self
+                                    .new_project_tags
+                                    .split(',')
+                                    .map(str::trim)
+                                    .filter(|t| !t.is_empty())
+                                    .map(str::to_string)
+                                    .collect(),
+                                folder: Some(self.new_project_folder.clone())
+                                    .filter(|f| !f.trim().is_empty()),
                             };
                             match store::cmd_create(args) {
                                 Ok(()) => {
@@ -1690,6 +2123,8 @@ impl SafeHoldApp {
                                     self.new_project_password.clear();
                                     self.new_project_confirm_password.clear();
                                     self.new_project_lock = false;
+                                    self.new_project_folder.clear();
+                                    self.new_project_tags.clear();
                                     self.show_create = false;
                                     self.refresh_config();
                                 }
@@ -1708,6 +2143,8 @@ impl SafeHoldApp {
                             self.new_project_password.clear();
                             self.new_project_confirm_password.clear();
                             self.new_project_lock = false;
+                            self.new_project_folder.clear();
+                            self.new_project_tags.clear();
                         }
                     });
                 });
@@ -1732,7 +2169,8 @@ impl SafeHoldApp {
 
                     ui.horizontal(|ui| {
                         ui.label("📝 Value:");
-                        if self.new_key.to_lowercase().contains("password")
+                        if self.new_val_generated
+                            || self.new_key.to_lowercase().contains("password")
                             || self.new_key.to_lowercase().contains("secret")
                             || self.new_key.to_lowercase().contains("token")
                         {
@@ -1740,6 +2178,22 @@ impl SafeHoldApp {
                         } else {
                             ui.text_edit_singleline(&mut self.new_val);
                         }
+                        if ui
+                            .button("🎲 Generate")
+                            .on_hover_text("Fill in a random value (at least 32 characters) that satisfies the project's policy")
+                            .clicked()
+                            && let Some(selected) = self.selected.clone()
+                        {
+                            match generate::policy_for(&selected)
+                                .and_then(|policy| generate::generate_for_policy(&policy))
+                            {
+                                Ok(value) => {
+                                    self.new_val = value;
+                                    self.new_val_generated = true;
+                                }
+                                Err(e) => self.show_error_dialog(&format!("Generate failed: {}", e)),
+                            }
+                        }
                     });
 
                     ui.separator();
@@ -1756,6 +2210,7 @@ impl SafeHoldApp {
                                 self.add_kv(selected);
                                 self.new_key.clear();
                                 self.new_val.clear();
+                                self.new_val_generated = false;
                                 self.show_add_credential = false;
                             }
                         }
@@ -1764,6 +2219,7 @@ impl SafeHoldApp {
                             self.show_add_credential = false;
                             self.new_key.clear();
                             self.new_val.clear();
+                            self.new_val_generated = false;
                         }
                     });
                 });
@@ -1832,6 +2288,10 @@ impl SafeHoldApp {
                                 },
                                 force: false,
                                 temp: false,
+                                strict: false,
+                                schema: None,
+                                ttl: None,
+                                command: Vec::new(),
                             }) {
                                 Ok(()) => {
                                     self.add_notification(
@@ -1891,6 +2351,14 @@ impl SafeHoldApp {
                                     .map(|s| s.to_string())
                                     .collect(),
                                 with_global: false,
+                                clean_env: false,
+                                env_allow: Vec::new(),
+                                env_deny: Vec::new(),
+                                exec: false,
+                                redact: false,
+                                file_vars: Vec::new(),
+                                strict: false,
+                                schema: None,
                             }) {
                                 Ok(()) => {
                                     self.add_notification(
@@ -1956,9 +2424,10 @@ impl SafeHoldApp {
                                             Ok(dir) => {
                                                 let password =
                                                     self.passwords.get(selected).cloned();
-                                                if let Ok(mut map) =
-                                                    read_env_map_dir(&dir, password.as_deref())
-                                                {
+                                                if let Ok(mut map) = read_env_map_dir(
+                                                    &dir,
+                                                    password.as_ref().map(|p| p.expose()),
+                                                ) {
                                                     map.insert(
                                                         self.update_key.clone(),
                                                         self.update_value.clone(),
@@ -1966,7 +2435,7 @@ impl SafeHoldApp {
                                                     if let Err(e) = write_env_map_dir(
                                                         &dir,
                                                         &map,
-                                                        password.as_deref(),
+                                                        password.as_ref().map(|p| p.expose()),
                                                     ) {
                                                         self.add_notification(
                                                             format!(
@@ -2158,11 +2627,11 @@ impl SafeHoldApp {
                                             match config::set_dir(selected) {
                                                 Ok(dir) => {
                                                     let password = self.passwords.get(selected).cloned();
-                                                    match read_env_map_dir(&dir, password.as_deref()) {
+                                                    match read_env_map_dir(&dir, password.as_ref().map(|p| p.expose())) {
                                                         Ok(mut map) => {
                                                             if map.contains_key(&self.delete_target) {
                                                                 map.remove(&self.delete_target);
-                                                                match write_env_map_dir(&dir, &map, password.as_deref()) {
+                                                                match write_env_map_dir(&dir, &map, password.as_ref().map(|p| p.expose())) {
                                                                     Ok(()) => {
                                                                         self.add_notification(format!("Deleted credential '{}'", self.delete_target), NotificationSeverity::Success);
                                                                         self.maps_cache.insert(selected.clone(), map);
@@ -2675,12 +3144,12 @@ impl SafeHoldApp {
     }
 
     /// Read environment map from a directory using GUI password handling
-    fn read_env_map(&self, dir: &PathBuf) -> Result<BTreeMap<String, String>> {
+    fn read_env_map(&self, dir: &Path) -> Result<SecretMap> {
         read_env_map_dir(dir, None)
     }
 
     /// Write environment map to a directory using GUI password handling
-    fn write_env_map(&self, dir: &PathBuf, map: &BTreeMap<String, String>) -> Result<()> {
+    fn write_env_map(&self, dir: &Path, map: &BTreeMap<String, String>) -> Result<()> {
         write_env_map_dir(dir, map, None)
     }
 
@@ -2780,7 +3249,7 @@ pub fn launch_gui() -> anyhow::Result<()> {
 
 // Helper functions for GUI to read/write maps using optional password input
 #[cfg(feature = "gui")]
-fn load_key_for_dir_gui(dir: &PathBuf, password_opt: Option<&str>) -> Result<[u8; 32]> {
+fn load_key_for_dir_gui(dir: &Path, password_opt: Option<&str>) -> Result<SecretKey> {
     let base = config::base_dir()?;
     let lp = lock_path(dir);
     if lp.exists() {
@@ -2794,16 +3263,16 @@ fn load_key_for_dir_gui(dir: &PathBuf, password_opt: Option<&str>) -> Result<[u8
 }
 
 #[cfg(feature = "gui")]
-fn read_env_map_dir(dir: &PathBuf, password_opt: Option<&str>) -> Result<BTreeMap<String, String>> {
+fn read_env_map_dir(dir: &Path, password_opt: Option<&str>) -> Result<SecretMap> {
     let key = load_key_for_dir_gui(dir, password_opt)?;
     let enc = fs::read(env_enc_path(dir)).unwrap_or_default();
     if enc.is_empty() {
-        return Ok(BTreeMap::new());
+        return Ok(SecretMap::new());
     }
     let pt = crypto::decrypt_with_key(&key, &enc)?;
     // Parse dotenv lines
     let s = String::from_utf8_lossy(&pt);
-    let mut map = BTreeMap::new();
+    let mut map = SecretMap::new();
     for line in s.lines() {
         if line.trim().is_empty() || line.trim_start().starts_with('#') {
             continue;
@@ -2817,14 +3286,17 @@ fn read_env_map_dir(dir: &PathBuf, password_opt: Option<&str>) -> Result<BTreeMa
 
 #[cfg(feature = "gui")]
 fn write_env_map_dir(
-    dir: &PathBuf,
+    dir: &Path,
     map: &BTreeMap<String, String>,
     password_opt: Option<&str>,
 ) -> Result<()> {
     let key = load_key_for_dir_gui(dir, password_opt)?;
-    let mut out = String::new();
+    let mut out = Zeroizing::new(String::new());
     for (k, v) in map {
-        out.push_str(&format!("{}={}\n", k, v));
+        out.push_str(k);
+        out.push('=');
+        out.push_str(v);
+        out.push('\n');
     }
     let ct = crypto::encrypt_with_key(&key, out.as_bytes())?;
     fs::write(env_enc_path(dir), ct)
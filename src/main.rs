@@ -15,24 +15,37 @@ use anyhow::Result;
 #[tokio::main]
 async fn main() {
     if let Err(e) = run().await {
-        // Fallback plain error (styles may or may not be initialized)
-        eprintln!("safehold error: {e}");
+        // Mirror a child's exit status from `run` instead of reporting an error
+        if let Some(exit) = e.downcast_ref::<operations::process::ChildExit>() {
+            exit.exit();
+        }
+        if cli::output::is_structured() {
+            cli::output::emit_error(&format!("{e:#}"));
+        } else {
+            // Fallback plain error (styles may or may not be initialized)
+            eprintln!("safehold error: {e}");
+        }
         std::process::exit(1);
     }
 }
 
 /// Build CLI, initialize styles, and execute command.
 async fn run() -> Result<()> {
+    // Before anything is decrypted: no core dumps, no ptrace, locked key pages
+    core::hardening::apply();
     let cmd = cli::cli::build_cli();
+    cli::output::init(cmd.output);
+    // Scripts asking for JSON/YAML get no banners or interactive first-run prompts
+    let structured = cli::output::is_structured();
 
     // Check version compatibility and show upgrade messages
-    if let Ok((is_new, old_version)) = core::config::check_version_compatibility() {
-        if !is_new {
-            if let Some(old_version) = old_version {
-                // Version upgrade detected
-                core::config::display_version_message(&old_version)?;
-            }
-        }
+    if !structured
+        && let Ok((is_new, old_version)) = core::config::check_version_compatibility()
+        && !is_new
+        && let Some(old_version) = old_version
+    {
+        // Version upgrade detected
+        core::config::display_version_message(&old_version)?;
     }
 
     // Check for updates on every command execution (non-blocking)
@@ -48,7 +61,7 @@ async fn run() -> Result<()> {
             gui_enabled: true,
             skip_setup: false,
         };
-    } else if should_run_install_prompt(&cmd) {
+    } else if !structured && should_run_install_prompt(&cmd) {
         // Handle installation prompt for first-time users
         let _install_config = utils::install::run_install_prompt()?;
     }
@@ -61,6 +74,7 @@ fn should_run_install_prompt(cmd: &cli::cli::Cli) -> bool {
     // Don't run install prompt for help commands, setup, or if explicitly skipped
     match &cmd.command {
         cli::cli::Commands::Setup { .. } => false, // Setup is manual
+        cli::cli::Commands::ClipboardClear { .. } => false, // Background helper
         _ => utils::install::is_first_run(),       // Only run on first run
     }
 }
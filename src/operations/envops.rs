@@ -1,24 +1,37 @@
 //! Environment operations: add/get/list/delete/export/run/show/clean
 use crate::cli::cli::{
-    CountArgs, ExportArgs, GlobalKeyArgs, GlobalKeyArgsForce, GlobalKeyValueArgs, ProjectKeyArgs,
-    ProjectKeyArgsForce, ProjectKeyValueArgs, ProjectTargetArgs, RunArgs,
+    CleanArgs, CountArgs, ExportArgs, GetArgs, GlobalKeyArgs, GlobalKeyArgsForce,
+    GlobalKeyValueArgs, ListArgs, ProjectKeyArgsForce, ProjectKeyValueArgs, RevealArgs, RunArgs,
 };
 use crate::core::config::{self, env_enc_path, lock_path};
 use crate::core::crypto::{self, LockInfo};
-use anyhow::{Result, bail};
+use crate::core::hardening;
+use crate::core::secret::{SecretKey, SecretMap};
+use crate::operations::expiry;
+use crate::operations::passwords::{self, Target};
+use crate::operations::process;
+use crate::operations::redact::Redactor;
+use crate::operations::schema;
+use crate::operations::secret_files::{self, SecretDir, SecretFile};
+use crate::operations::typed;
+use crate::utils::{app_settings, clipboard, duration};
+use anyhow::{Context, Result, bail};
 // use dotenvy; // parsing implemented manually
-use crate::cli::styles;
+use crate::cli::{output, styles};
+use serde::Serialize;
 use std::collections::BTreeMap;
 use std::fs;
 use std::io::{self, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use walkdir::WalkDir;
+use zeroize::Zeroizing;
 
 /// Parse dotenv-style bytes into a sorted map.
-fn read_env_map_from_bytes(bytes: &[u8]) -> Result<BTreeMap<String, String>> {
+pub(crate) fn read_env_map_from_bytes(bytes: &[u8]) -> Result<SecretMap> {
     // Parse as dotenv lines
     let s = String::from_utf8_lossy(bytes);
-    let mut map = BTreeMap::new();
+    let mut map = SecretMap::new();
     for line in s.lines() {
         if line.trim().is_empty() || line.trim_start().starts_with('#') {
             continue;
@@ -30,17 +43,20 @@ fn read_env_map_from_bytes(bytes: &[u8]) -> Result<BTreeMap<String, String>> {
     Ok(map)
 }
 
-/// Serialize map to dotenv-style string.
-fn write_env_string(map: &BTreeMap<String, String>) -> String {
-    let mut out = String::new();
+/// Serialize map to dotenv-style string, wiped when dropped.
+fn write_env_string(map: &BTreeMap<String, String>) -> Zeroizing<String> {
+    let mut out = Zeroizing::new(String::new());
     for (k, v) in map {
-        out.push_str(&format!("{}={}\n", k, v));
+        out.push_str(k);
+        out.push('=');
+        out.push_str(v);
+        out.push('\n');
     }
     out
 }
 
 /// Resolve a project directory from id or name; supports "global".
-fn resolve_set_dir(id_or_name: &str) -> Result<PathBuf> {
+pub(crate) fn resolve_set_dir(id_or_name: &str) -> Result<PathBuf> {
     let cfg = config::load_config()?;
     if id_or_name == "global" {
         return config::global_dir();
@@ -57,30 +73,28 @@ fn resolve_set_dir(id_or_name: &str) -> Result<PathBuf> {
 }
 
 /// Load encryption key for dir (password-derived if locked, else app key).
-/// Uses SAFEHOLD_PASSWORD if set. Checks Global Master Lock first.
-fn load_key_for_dir(dir: &PathBuf) -> Result<[u8; 32]> {
+/// Passwords come from `passwords` (files, descriptors, env vars, a helper
+/// command) before prompting. Checks Global Master Lock first.
+pub(crate) fn load_key_for_dir(dir: &Path) -> Result<SecretKey> {
     let base = config::base_dir()?;
 
     // Check Global Master Lock first
     if crate::operations::master_lock::is_master_lock_enabled() {
         // Global Master Lock is enabled - use master password for ALL projects
-        let master_password = match std::env::var("SAFEHOLD_MASTER_PASSWORD") {
-            Ok(p) => p,
-            Err(_) => {
-                crate::cli::styles::info("🔒 Global Master Lock is active");
-                rpassword::prompt_password("Master Password: ")?
-            }
-        };
+        if !passwords::available(Target::Master) {
+            crate::cli::styles::info("🔒 Global Master Lock is active");
+        }
+        let master_password = passwords::password(Target::Master)?;
 
         // Verify master password
-        if !crate::operations::master_lock::verify_master_password(&master_password)? {
+        if !crate::operations::master_lock::verify_master_password(master_password.expose())? {
             anyhow::bail!("❌ Invalid master password");
         }
 
         // Use master password to derive key for this project
         // We need a consistent salt for master lock, so use a fixed one
         let master_salt = b"safehold_master_lock_salt_v1";
-        let key = crypto::derive_key_from_password_and_salt(&master_password, master_salt)?;
+        let key = crypto::derive_key_from_password_and_salt(master_password.expose(), master_salt)?;
         return Ok(key);
     }
 
@@ -88,33 +102,117 @@ fn load_key_for_dir(dir: &PathBuf) -> Result<[u8; 32]> {
     let lock_path = lock_path(dir);
     if lock_path.exists() {
         let lock: LockInfo = serde_json::from_slice(&fs::read(&lock_path)?)?;
-        let password = match std::env::var("SAFEHOLD_PASSWORD") {
-            Ok(p) => p,
-            Err(_) => rpassword::prompt_password("Password: ")?,
-        };
-        let key = crypto::derive_key_from_password(&password, &lock)?;
+        let (id, name) = project_id_and_name(dir);
+        let password = passwords::password(Target::Project {
+            id: &id,
+            name: &name,
+        })?;
+        let key = crypto::derive_key_from_password(password.expose(), &lock)?;
         Ok(key)
     } else {
         crypto::load_app_key(&base)
     }
 }
 
+/// ID and name of the project stored in `dir` (both `global` for the global
+/// project), so prompts and password sources know which one is unlocked.
+fn project_id_and_name(dir: &Path) -> (String, String) {
+    let id = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = config::load_config()
+        .ok()
+        .and_then(|cfg| cfg.sets.into_iter().find(|s| s.id == id))
+        .map(|s| s.name)
+        .unwrap_or_else(|| id.clone());
+    (id, name)
+}
+
 /// Decrypt and read env map from a project directory.
-fn read_env_map(dir: &PathBuf) -> Result<BTreeMap<String, String>> {
-    let key = load_key_for_dir(dir)?;
+pub(crate) fn read_env_map(dir: &Path) -> Result<SecretMap> {
+    read_env_map_with_key(dir, &load_key_for_dir(dir)?)
+}
+
+/// Like `read_env_map`, with a key already loaded by `load_key_for_dir`.
+pub(crate) fn read_env_map_with_key(dir: &Path, key: &SecretKey) -> Result<SecretMap> {
     let enc = fs::read(env_enc_path(dir)).unwrap_or_default();
     if enc.is_empty() {
-        return Ok(BTreeMap::new());
+        return Ok(SecretMap::new());
     }
-    let pt = crypto::decrypt_with_key(&key, &enc)?;
+    let pt = crypto::decrypt_with_key(key, &enc)?;
     read_env_map_from_bytes(&pt)
 }
 
-/// Encrypt and write env map to a project directory.
-fn write_env_map(dir: &PathBuf, map: &BTreeMap<String, String>) -> Result<()> {
-    let key = load_key_for_dir(dir)?;
+/// Decrypted env maps labelled by project name.
+pub(crate) type LabelledMaps = Vec<(String, SecretMap)>;
+
+/// Project directories and their loaded keys, labelled by project name.
+pub(crate) type LabelledKeys = Vec<(String, PathBuf, SecretKey)>;
+
+/// Keys of the projects that open without a prompt (`global` for the global project).
+///
+/// With `only`, just that project is unlocked, prompting for its password if
+/// needed. Otherwise every project that opens without a prompt is included:
+/// unlocked ones, plus locked ones whose password is available without a
+/// prompt (see `passwords`). Returns the keys and how many projects were skipped.
+pub(crate) fn readable_project_keys(only: Option<&str>) -> Result<(LabelledKeys, usize)> {
+    if let Some(project) = only {
+        let dir = resolve_set_dir(project)?;
+        let key = load_key_for_dir(&dir)?;
+        return Ok((vec![(project.to_string(), dir, key)], 0));
+    }
+    let cfg = config::load_config()?;
+    let mut dirs = vec![("global".to_string(), config::global_dir()?)];
+    for s in cfg.sets {
+        dirs.push((s.name, config::set_dir(&s.id)?));
+    }
+    let master_locked = crate::operations::master_lock::is_master_lock_enabled();
+    let mut keys = Vec::new();
+    let mut skipped = 0usize;
+    for (name, dir) in dirs {
+        let opens = if master_locked {
+            passwords::available(Target::Master)
+        } else {
+            let (id, _) = project_id_and_name(&dir);
+            !lock_path(&dir).exists()
+                || passwords::available(Target::Project {
+                    id: &id,
+                    name: &name,
+                })
+        };
+        match opens.then(|| load_key_for_dir(&dir)) {
+            Some(Ok(key)) => keys.push((name, dir, key)),
+            _ => skipped += 1,
+        }
+    }
+    Ok((keys, skipped))
+}
+
+/// Decrypted maps of the projects `readable_project_keys` opens, labelled by
+/// project name. Returns the maps and how many projects were skipped.
+pub(crate) fn readable_env_maps(only: Option<&str>) -> Result<(LabelledMaps, usize)> {
+    let (keys, mut skipped) = readable_project_keys(only)?;
+    let mut maps = Vec::new();
+    for (name, dir, key) in keys {
+        match read_env_map_with_key(&dir, &key) {
+            Ok(map) => maps.push((name, map)),
+            Err(e) if only.is_some() => return Err(e),
+            Err(_) => skipped += 1,
+        }
+    }
+    Ok((maps, skipped))
+}
+
+/// Encrypt and write env map to a project directory, with a key loaded by
+/// `load_key_for_dir`.
+pub(crate) fn write_env_map_with_key(
+    dir: &Path,
+    key: &SecretKey,
+    map: &BTreeMap<String, String>,
+) -> Result<()> {
     let s = write_env_string(map);
-    let ct = crypto::encrypt_with_key(&key, s.as_bytes())?;
+    let ct = crypto::encrypt_with_key(key, s.as_bytes())?;
     fs::write(env_enc_path(dir), ct)?;
     Ok(())
 }
@@ -122,8 +220,10 @@ fn write_env_map(dir: &PathBuf, map: &BTreeMap<String, String>) -> Result<()> {
 /// Add or replace a key/value in a project. Reads value from stdin if not provided.
 pub fn cmd_add(args: ProjectKeyValueArgs) -> Result<()> {
     let dir = resolve_set_dir(&args.project)?;
-    let mut map = read_env_map(&dir)?;
+    let key = load_key_for_dir(&dir)?;
+    let mut map = read_env_map_with_key(&dir, &key)?;
     let value = match args.value {
+        _ if args.typed.is_set() => typed::build_value(None, &args.typed)?,
         Some(v) => v,
         None => {
             // read from stdin
@@ -132,30 +232,124 @@ pub fn cmd_add(args: ProjectKeyValueArgs) -> Result<()> {
             buf.trim_end().to_string()
         }
     };
-    map.insert(args.key, value);
-    write_env_map(&dir, &map)?;
+    typed::validate_value(&value)?;
+    map.insert(args.key.clone(), value);
+    write_env_map_with_key(&dir, &key, &map)?;
+    expiry::record_change(&dir, &key, &args.key)?;
     styles::ok("Added");
     Ok(())
 }
 
-/// Print a single value for the given key.
-pub fn cmd_get(args: ProjectKeyArgs) -> Result<()> {
+/// Print a single value for the given key, or copy it when stdout is a terminal.
+pub fn cmd_get(args: GetArgs) -> Result<()> {
     let dir = resolve_set_dir(&args.project)?;
     let map = read_env_map(&dir)?;
-    if let Some(v) = map.get(&args.key) {
+    let Some(v) = map.get(&args.key) else {
+        bail!("key not found");
+    };
+    // Keep the value off the screen (and out of scrollback) when a person is looking
+    if args.print || output::is_structured() || !atty::is(atty::Stream::Stdout) {
         println!("{}", v);
     } else {
-        bail!("key not found");
+        let delay = match clipboard::copy_secret(v) {
+            Ok(delay) => delay,
+            Err(e) => bail!("{:#}; pass --print to print the value instead", e),
+        };
+        styles::success(format!(
+            "📋 Copied '{}' to the clipboard{}",
+            args.key,
+            clipboard::clear_note(delay)
+        ));
     }
     Ok(())
 }
 
-/// List all key=value pairs in a project.
-pub fn cmd_list(args: ProjectTargetArgs) -> Result<()> {
+/// How `list`, `global-list` and `show-all` show credential values.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ValueDisplay {
+    Masked,
+    Revealed,
+    KeysOnly,
+}
+
+impl ValueDisplay {
+    fn from_args(args: &RevealArgs) -> Self {
+        if args.keys_only {
+            ValueDisplay::KeysOnly
+        } else if args.reveal || app_settings::reveal_values_default() {
+            ValueDisplay::Revealed
+        } else {
+            ValueDisplay::Masked
+        }
+    }
+
+    /// Text shown for a value; `None` with `--keys-only`.
+    fn value(self, value: &str) -> Option<String> {
+        match self {
+            ValueDisplay::Masked => Some(mask_value(value)),
+            ValueDisplay::Revealed => Some(value.to_string()),
+            ValueDisplay::KeysOnly => None,
+        }
+    }
+
+    fn line(self, key: &str, value: &str) -> String {
+        match self.value(value) {
+            Some(shown) => format!("{}={}", key, shown),
+            None => key.to_string(),
+        }
+    }
+
+    /// Credentials as they appear in JSON/YAML documents.
+    fn doc(self, map: &BTreeMap<String, String>) -> BTreeMap<String, Option<String>> {
+        map.iter()
+            .map(|(k, v)| (k.clone(), self.value(v)))
+            .collect()
+    }
+
+    fn hint(self) {
+        if self == ValueDisplay::Masked {
+            styles::info(
+                "Values are masked; pass --reveal to show them or --keys-only to hide them",
+            );
+        }
+    }
+}
+
+/// Mask a value for display: its length, plus the last 4 characters when the
+/// value is long enough that they give little away.
+pub(crate) fn mask_value(value: &str) -> String {
+    let len = value.chars().count();
+    if len >= 12 {
+        let tail: String = value.chars().skip(len - 4).collect();
+        format!("••••{} ({} chars)", tail, len)
+    } else {
+        format!("•••• ({} chars)", len)
+    }
+}
+
+/// `list`/`global-list --output json|yaml` document.
+#[derive(Serialize)]
+struct CredentialsDoc<'a> {
+    project: &'a str,
+    credentials: BTreeMap<String, Option<String>>,
+}
+
+/// List all key=value pairs in a project, values masked unless revealed.
+pub fn cmd_list(args: ListArgs) -> Result<()> {
     let dir = resolve_set_dir(&args.project)?;
     let map = read_env_map(&dir)?;
-    for (k, v) in map {
-        println!("{}={}", k, v);
+    let display = ValueDisplay::from_args(&args.display);
+    if output::is_structured() {
+        return output::emit(&CredentialsDoc {
+            project: &args.project,
+            credentials: display.doc(&map),
+        });
+    }
+    for (k, v) in &map {
+        println!("{}", display.line(k, v));
+    }
+    if !map.is_empty() {
+        display.hint();
     }
     Ok(())
 }
@@ -163,7 +357,8 @@ pub fn cmd_list(args: ProjectTargetArgs) -> Result<()> {
 /// Delete a key in a project (no-op if missing).
 pub fn cmd_delete(args: ProjectKeyArgsForce) -> Result<()> {
     let dir = resolve_set_dir(&args.project)?;
-    let mut map = read_env_map(&dir)?;
+    let key = load_key_for_dir(&dir)?;
+    let mut map = read_env_map_with_key(&dir, &key)?;
 
     if !map.contains_key(&args.key) {
         styles::warn("Key not found");
@@ -197,7 +392,8 @@ pub fn cmd_delete(args: ProjectKeyArgsForce) -> Result<()> {
     }
 
     if map.remove(&args.key).is_some() {
-        write_env_map(&dir, &map)?;
+        write_env_map_with_key(&dir, &key, &map)?;
+        expiry::forget_key(&dir, &key, &args.key)?;
         styles::ok("Deleted");
     } else {
         styles::warn("Key not found");
@@ -206,6 +402,10 @@ pub fn cmd_delete(args: ProjectKeyArgsForce) -> Result<()> {
 }
 
 /// Export a project or global into a .env file; supports temp mode and overwrite.
+///
+/// In temp mode the file is created owner-only, kept while a command given after
+/// `--` runs (or until SIGINT/SIGTERM/SIGHUP when there is none), and shredded on
+/// every exit path; `--ttl` removes it earlier.
 pub fn cmd_export(args: ExportArgs) -> Result<()> {
     let dir = if args.global {
         config::global_dir()?
@@ -217,90 +417,392 @@ pub fn cmd_export(args: ExportArgs) -> Result<()> {
         resolve_set_dir(project)?
     };
     let pb = styles::spinner("Decrypting and writing .env...");
-    let map = read_env_map(&dir)?;
+    let mut map = typed::flatten(&*read_env_map(&dir)?);
+    schema::enforce(&dir, args.schema.as_deref(), args.strict, &mut map)?;
     let filename = args.file.unwrap_or_else(|| ".env".into());
     if std::path::Path::new(&filename).exists() && !args.force {
         bail!("{} exists, use --force", filename);
     }
     let content = write_env_string(&map);
-    fs::write(&filename, content)?;
-    if args.temp {
-        // best-effort delete on exit
-        let name = filename.clone();
+    if !args.temp {
+        fs::write(&filename, content)?;
+        styles::ok(format!(".env written: {}", filename));
+        styles::finish_spinner(pb, "Done");
+        return Ok(());
+    }
+
+    // Without a command, SIGINT/SIGTERM/SIGHUP (Ctrl+C / close on Windows) end the
+    // wait; installed before the file exists so no signal can strand it on disk
+    let signalled = if args.command.is_empty() {
+        let (tx, rx) = std::sync::mpsc::channel();
         ctrlc::set_handler(move || {
-            let _ = std::fs::remove_file(&name);
-            std::process::exit(0);
+            let _ = tx.send(());
         })
-        .ok();
+        .map_err(|e| anyhow::anyhow!("failed to install signal handler: {}", e))?;
+        Some(rx)
+    } else {
+        None
+    };
+    // Shredded when this guard drops: normal return, error or panic unwinding
+    let file = Arc::new(SecretFile::create(
+        Path::new(&filename),
+        content.as_bytes(),
+        args.force,
+    )?);
+    styles::finish_spinner(pb, "Done");
+    let ttl_note = args
+        .ttl
+        .map(|t| format!(", or after {}", duration::format_duration(t)))
+        .unwrap_or_default();
+
+    let result = if let Some((prog, rest)) = args.command.split_first() {
         styles::info(format!(
-            "Temporary .env written: {} (will delete on Ctrl+C or process exit)",
-            filename
+            "Temporary .env written: {} (removed when '{}' exits{})",
+            filename, prog, ttl_note
         ));
+        // The TTL bounds the file's lifetime, not the command's
+        if let Some(ttl) = args.ttl {
+            let timer = Arc::clone(&file);
+            std::thread::spawn(move || {
+                std::thread::sleep(ttl);
+                if let Some(path) = timer.shred() {
+                    styles::info(format!(
+                        "⌛ TTL expired, temporary .env removed: {}",
+                        path.display()
+                    ));
+                }
+            });
+        }
+        let mut cmd = std::process::Command::new(prog);
+        cmd.args(rest);
+        process::EnvFilter::default().apply(&mut cmd);
+        process::spawn_and_wait(&mut cmd, None).and_then(|status| {
+            match process::ChildExit::from_status(status) {
+                Some(exit) => Err(exit.into()),
+                None => Ok(()),
+            }
+        })
     } else {
-        styles::ok(format!(".env written: {}", filename));
+        styles::info(format!(
+            "Temporary .env written: {} (removed on Ctrl+C or termination{})",
+            filename, ttl_note
+        ));
+        if let Some(rx) = signalled {
+            match args.ttl {
+                Some(ttl) => {
+                    let _ = rx.recv_timeout(ttl);
+                }
+                None => {
+                    let _ = rx.recv();
+                }
+            }
+        }
+        Ok(())
+    };
+    if let Some(path) = file.shred() {
+        styles::success(format!("🧹 Temporary .env removed: {}", path.display()));
     }
-    styles::finish_spinner(pb, "Done");
-    Ok(())
+    result
 }
 
 /// Run a program with environment variables injected from a project and optionally from global.
+///
+/// The child's exit status is passed through unchanged (see `process::ChildExit`), and
+/// termination signals received while waiting are forwarded to it. With `--redact`
+/// its output is filtered so injected values never reach the terminal or CI logs, and
+/// `--file-var` keys are handed over as paths to private files shredded on exit.
 pub fn cmd_run(args: RunArgs) -> Result<()> {
     let dir = resolve_set_dir(&args.project)?;
-    let mut map = read_env_map(&dir)?;
+    let key = load_key_for_dir(&dir)?;
+    let mut map = read_env_map_with_key(&dir, &key)?;
     if args.with_global {
         let gdir = config::global_dir()?;
-        let gmap = read_env_map(&gdir)?;
-        map.extend(gmap);
+        let gkey = load_key_for_dir(&gdir)?;
+        let gmap = read_env_map_with_key(&gdir, &gkey)?;
+        // Global values win, so only warn about what is actually injected
+        expiry::warn_due(
+            &dir,
+            &key,
+            &args.project,
+            map.keys().filter(|k| !gmap.contains_key(*k)),
+        );
+        expiry::warn_due(&gdir, &gkey, "global", gmap.keys());
+        map.extend(gmap.iter().map(|(k, v)| (k.clone(), v.clone())));
+    } else {
+        expiry::warn_due(&dir, &key, &args.project, map.keys());
     }
+    let mut map = typed::flatten(&map);
+    schema::enforce(&dir, args.schema.as_deref(), args.strict, &mut map)?;
     // Prepare command
     let mut iter = args.command.iter();
-    let prog = iter.next().expect("command required");
+    let prog = iter
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("command required"))?;
     let mut cmd = std::process::Command::new(prog);
     cmd.args(iter);
-    cmd.envs(map);
-    let status = cmd.status()?;
-    if !status.success() {
-        bail!("process exited with status {:?}", status.code());
+    if args.clean_env {
+        cmd.env_clear();
+    } else {
+        process::EnvFilter::new(&args.env_allow, &args.env_deny).apply(&mut cmd);
     }
-    Ok(())
+    let redactor = args
+        .redact
+        .then(|| Arc::new(Redactor::from_map(&map)))
+        .filter(|r| !r.is_empty());
+    // Values requested as files; the directory is shredded when this guard drops
+    let _secret_dir = if args.file_vars.is_empty() {
+        None
+    } else {
+        let mut secret_dir = SecretDir::create("safehold-run")?;
+        for key in &args.file_vars {
+            let value = map
+                .get(key)
+                .ok_or_else(|| anyhow::anyhow!("key '{}' not found for --file-var", key))?;
+            let path = secret_dir.write(key, value.as_bytes())?;
+            map.insert(key.clone(), path.to_string_lossy().into_owned());
+        }
+        Some(secret_dir)
+    };
+    cmd.envs(&map);
+    if args.exec {
+        return process::exec(&mut cmd);
+    }
+    let status = process::spawn_and_wait(&mut cmd, redactor)?;
+    match process::ChildExit::from_status(status) {
+        Some(exit) => Err(exit.into()),
+        None => Ok(()),
+    }
+}
+
+/// `show-all --output json|yaml` document; unreadable stores are `null`.
+#[derive(Serialize)]
+struct ShowAllDoc {
+    global: Option<BTreeMap<String, Option<String>>>,
+    projects: Vec<ProjectCredentialsDoc>,
+}
+
+#[derive(Serialize)]
+struct ProjectCredentialsDoc {
+    id: String,
+    name: String,
+    credentials: Option<BTreeMap<String, Option<String>>>,
 }
 
 /// Show all projects and their keys to stdout.
-pub fn cmd_show_all() -> Result<()> {
+pub fn cmd_show_all(args: RevealArgs) -> Result<()> {
     let cfg = config::load_config()?;
+    let display = ValueDisplay::from_args(&args);
+    if output::is_structured() {
+        let mut projects = Vec::new();
+        for s in cfg.sets.into_iter().filter(|s| !s.archived) {
+            let credentials = read_env_map(&config::set_dir(&s.id)?).ok();
+            projects.push(ProjectCredentialsDoc {
+                id: s.id,
+                name: s.name,
+                credentials: credentials.map(|map| display.doc(&map)),
+            });
+        }
+        return output::emit(&ShowAllDoc {
+            global: read_env_map(&config::global_dir()?)
+                .ok()
+                .map(|map| display.doc(&map)),
+            projects,
+        });
+    }
     styles::info("GLOBAL:");
     let gdir = config::global_dir()?;
     if let Ok(map) = read_env_map(&gdir) {
-        for (k, v) in map {
-            println!("  {}={}", k, v);
+        for (k, v) in &map {
+            println!("  {}", display.line(k, v));
         }
     }
-    for s in cfg.sets {
+    for s in cfg.sets.into_iter().filter(|s| !s.archived) {
         styles::info(format!("PROJECT {} ({})", s.id, s.name));
         let dir = config::set_dir(&s.id)?;
         if let Ok(map) = read_env_map(&dir) {
-            for (k, v) in map {
-                println!("  {}={}", k, v);
+            for (k, v) in &map {
+                println!("  {}", display.line(k, v));
             }
         }
     }
+    display.hint();
     Ok(())
 }
 
-/// Recursively remove plaintext .env files in current directory tree.
-pub fn cmd_clean() -> Result<()> {
+/// Default globs for plaintext env files picked up by `clean`.
+const CLEAN_DEFAULT_PATTERNS: [&str; 2] = [".env", ".env.*"];
+
+/// Dependency, build and VCS folders never descended into by `clean` and `scan`.
+pub(crate) const DEPENDENCY_DIRS: [&str; 5] =
+    [".git/", "node_modules/", "target/", "vendor/", ".venv/"];
+
+/// Committed templates that `clean` and the git hooks leave alone.
+pub(crate) const CLEAN_TEMPLATE_FILES: [&str; 3] = [".env.example", ".env.sample", ".env.template"];
+
+/// Find and shred plaintext .env files under a directory tree.
+///
+/// Directories ignored by `.gitignore`/`.ignore` (unless `--no-ignore`) and the
+/// default exclusions are not descended into; matching files themselves are
+/// still found when gitignored, since that is where stray secrets hide. Matches
+/// are listed and confirmed before anything is removed, and with `--import` each
+/// file's variables are stored in a project first.
+pub fn cmd_clean(args: CleanArgs) -> Result<()> {
+    let files = find_clean_targets(&args)?;
+    if files.is_empty() {
+        styles::info("✨ No plaintext .env files found");
+        return Ok(());
+    }
+
+    styles::header(format!("🧹 Found {} plaintext env file(s)", files.len()));
+    for file in &files {
+        styles::bullet(file.display().to_string());
+    }
+    if args.dry_run {
+        styles::info("👀 Dry run: nothing was removed");
+        return Ok(());
+    }
+
+    let action = match &args.import {
+        Some(project) => format!("imported into '{}' and then shredded", project),
+        None => "shredded (overwritten and deleted)".to_string(),
+    };
+    let message = format!(
+        "🗑️ These {} file(s) will be {}\n⚠️ This action cannot be undone.",
+        files.len(),
+        action
+    );
+    if !ask_confirmation(&message, args.force) {
+        styles::info("❌ Clean cancelled");
+        return Ok(());
+    }
+
+    let targets = match &args.import {
+        Some(project) => import_env_files(project, &files)?,
+        None => files,
+    };
+
     let mut removed = 0usize;
-    for entry in WalkDir::new(".").into_iter().filter_map(|e| e.ok()) {
-        if entry.file_type().is_file() && entry.file_name().to_string_lossy() == ".env" {
-            // delete file
-            let _ = fs::remove_file(entry.path());
-            removed += 1;
+    let mut failed = 0usize;
+    for file in &targets {
+        match secret_files::shred_file(file) {
+            Ok(()) => removed += 1,
+            Err(e) => {
+                styles::error(format!("Failed to remove {}: {:#}", file.display(), e));
+                failed += 1;
+            }
         }
     }
     styles::success(format!("🧹 Removed {} .env files", removed));
+    if failed > 0 {
+        bail!("{} file(s) could not be removed", failed);
+    }
     Ok(())
 }
 
+/// Walk `args.path` and collect files matching the clean patterns.
+fn find_clean_targets(args: &CleanArgs) -> Result<Vec<PathBuf>> {
+    if !args.path.is_dir() {
+        bail!("{} is not a directory", args.path.display());
+    }
+    let mut overrides = ignore::overrides::OverrideBuilder::new(&args.path);
+    let patterns: Vec<&str> = if args.patterns.is_empty() {
+        CLEAN_DEFAULT_PATTERNS.to_vec()
+    } else {
+        args.patterns.iter().map(String::as_str).collect()
+    };
+    for pattern in patterns {
+        overrides
+            .add(pattern)
+            .with_context(|| format!("invalid pattern '{}'", pattern))?;
+    }
+    // Later globs win, so exclusions override the patterns above
+    let excludes = DEPENDENCY_DIRS
+        .iter()
+        .chain(CLEAN_TEMPLATE_FILES.iter())
+        .copied()
+        .chain(args.excludes.iter().map(String::as_str));
+    for exclude in excludes {
+        overrides
+            .add(&format!("!{}", exclude))
+            .with_context(|| format!("invalid exclude '{}'", exclude))?;
+    }
+
+    let walker = ignore::WalkBuilder::new(&args.path)
+        .hidden(false)
+        .require_git(false)
+        .git_ignore(!args.no_ignore)
+        .git_exclude(!args.no_ignore)
+        .git_global(!args.no_ignore)
+        .ignore(!args.no_ignore)
+        .parents(!args.no_ignore)
+        .overrides(overrides.build()?)
+        .build();
+
+    let mut files = Vec::new();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                styles::warn(format!("Skipping unreadable path: {}", e));
+                continue;
+            }
+        };
+        if entry.file_type().is_some_and(|t| t.is_file()) {
+            files.push(entry.into_path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Merge env files into a project; returns the files that are safe to shred.
+///
+/// A file defining a key the project already holds with a different value is
+/// skipped (and kept on disk) so no secret is lost.
+fn import_env_files(project: &str, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let dir = resolve_set_dir(project)?;
+    // One key (and one password prompt) for reading and writing
+    let key = load_key_for_dir(&dir)?;
+    let mut map = read_env_map_with_key(&dir, &key)?;
+    let mut imported = Vec::new();
+    let mut changed = Vec::new();
+    for file in files {
+        let bytes = fs::read(file).with_context(|| format!("read {}", file.display()))?;
+        let incoming = read_env_map_from_bytes(&bytes)?;
+        let conflicts: Vec<&str> = incoming
+            .iter()
+            .filter(|(k, v)| map.get(*k).is_some_and(|existing| existing != *v))
+            .map(|(k, _)| k.as_str())
+            .collect();
+        if !conflicts.is_empty() {
+            styles::warn(format!(
+                "Keeping {}: {} already set differently in '{}'",
+                file.display(),
+                conflicts.join(", "),
+                project
+            ));
+            continue;
+        }
+        for (k, v) in &incoming {
+            if map.insert(k.clone(), v.clone()).is_none() {
+                changed.push(k.clone());
+            }
+        }
+        imported.push(file.clone());
+    }
+    write_env_map_with_key(&dir, &key, &map)?;
+    for name in &changed {
+        expiry::record_change(&dir, &key, name)?;
+    }
+    styles::ok(format!(
+        "📥 Imported {} new key(s) from {} file(s) into '{}'",
+        changed.len(),
+        imported.len(),
+        project
+    ));
+    Ok(imported)
+}
+
 /// Update/modify a credential value in a project.
 ///
 /// This function allows updating an existing credential's value within a specific project.
@@ -314,7 +816,8 @@ pub fn cmd_clean() -> Result<()> {
 /// * `Result<()>` - Success or error if the key doesn't exist or operation fails
 pub fn cmd_update(args: ProjectKeyValueArgs) -> Result<()> {
     let dir = resolve_set_dir(&args.project)?;
-    let mut map = read_env_map(&dir)?;
+    let key = load_key_for_dir(&dir)?;
+    let mut map = read_env_map_with_key(&dir, &key)?;
 
     // Check if key exists
     if !map.contains_key(&args.key) {
@@ -326,6 +829,9 @@ pub fn cmd_update(args: ProjectKeyValueArgs) -> Result<()> {
     }
 
     let value = match args.value {
+        _ if args.typed.is_set() => {
+            typed::build_value(map.get(&args.key).map(String::as_str), &args.typed)?
+        }
         Some(v) => v,
         None => {
             use std::io::{self, Write};
@@ -337,8 +843,10 @@ pub fn cmd_update(args: ProjectKeyValueArgs) -> Result<()> {
         }
     };
 
+    typed::validate_value(&value)?;
     map.insert(args.key.clone(), value);
-    write_env_map(&dir, &map)?;
+    write_env_map_with_key(&dir, &key, &map)?;
+    expiry::record_change(&dir, &key, &args.key)?;
     styles::success(format!(
         "Updated credential '{}' in project '{}'",
         args.key, args.project
@@ -346,6 +854,34 @@ pub fn cmd_update(args: ProjectKeyValueArgs) -> Result<()> {
     Ok(())
 }
 
+/// `count --output json|yaml` document; `global` is `null` unless counted.
+#[derive(Serialize)]
+struct CountDoc<'a> {
+    total: usize,
+    global: Option<usize>,
+    projects: Vec<ProjectCountDoc<'a>>,
+}
+
+#[derive(Serialize)]
+struct ProjectCountDoc<'a> {
+    id: &'a str,
+    name: &'a str,
+    folder: Option<&'a str>,
+    /// `null` when the project could not be opened
+    count: Option<usize>,
+}
+
+impl<'a> ProjectCountDoc<'a> {
+    fn new(s: &'a config::SetMeta, count: Option<usize>) -> Self {
+        ProjectCountDoc {
+            id: &s.id,
+            name: &s.name,
+            folder: s.folder.as_deref(),
+            count,
+        }
+    }
+}
+
 /// Count credentials in projects with various display options.
 ///
 /// This function provides comprehensive statistics about credential storage:
@@ -367,6 +903,20 @@ pub fn cmd_count(args: CountArgs) -> Result<()> {
         let dir = resolve_set_dir(&project)?;
         let map = read_env_map(&dir)?;
         let count = map.len();
+        if output::is_structured() {
+            let meta = cfg
+                .sets
+                .iter()
+                .find(|s| s.id == project || s.name == project);
+            return output::emit(&CountDoc {
+                total: count,
+                global: meta.is_none().then_some(count),
+                projects: meta
+                    .map(|s| ProjectCountDoc::new(s, Some(count)))
+                    .into_iter()
+                    .collect(),
+            });
+        }
         styles::info(format!(
             "📊 Project '{}' has {} credential(s)",
             project, count
@@ -385,16 +935,28 @@ pub fn cmd_count(args: CountArgs) -> Result<()> {
             }
         }
 
-        // Count project credentials
+        // Count project credentials; unreadable projects have no count
         let mut project_counts = Vec::new();
         for s in &cfg.sets {
             let dir = config::set_dir(&s.id)?;
-            if let Ok(map) = read_env_map(&dir) {
-                let count = map.len();
-                total += count;
-                project_counts.push((s.name.clone(), s.id.clone(), count));
-            }
+            let count = read_env_map(&dir).ok().map(|map| map.len());
+            total += count.unwrap_or(0);
+            project_counts.push((s, count));
+        }
+
+        if output::is_structured() {
+            return output::emit(&CountDoc {
+                total,
+                global: args.include_global.then_some(global_count),
+                projects: project_counts
+                    .iter()
+                    .map(|(s, count)| ProjectCountDoc::new(s, *count))
+                    .collect(),
+            });
         }
+        let readable = project_counts
+            .into_iter()
+            .filter_map(|(s, count)| count.map(|count| (s, count)));
 
         styles::header("📊 Credential Count Summary");
         if args.include_global {
@@ -402,9 +964,29 @@ pub fn cmd_count(args: CountArgs) -> Result<()> {
             styles::divider();
         }
 
-        if args.detailed {
-            for (name, _id, count) in project_counts {
-                styles::bullet(format!("📁 {}: {} credential(s)", name, count));
+        if args.by_folder {
+            let mut folders: BTreeMap<Option<&str>, Vec<(&str, usize)>> = BTreeMap::new();
+            for (s, count) in readable {
+                folders
+                    .entry(s.folder.as_deref())
+                    .or_default()
+                    .push((&s.name, count));
+            }
+            for (folder, projects) in folders {
+                let sum: usize = projects.iter().map(|(_, c)| c).sum();
+                styles::bullet(format!(
+                    "📂 {}: {} credential(s)",
+                    folder.unwrap_or("(no folder)"),
+                    sum
+                ));
+                for (name, count) in projects {
+                    println!("    📁 {}: {} credential(s)", name, count);
+                }
+            }
+            styles::divider();
+        } else if args.detailed {
+            for (s, count) in readable {
+                styles::bullet(format!("📁 {}: {} credential(s)", s.name, count));
             }
             styles::divider();
         }
@@ -431,9 +1013,11 @@ pub fn cmd_count(args: CountArgs) -> Result<()> {
 /// * `Result<()>` - Success or error if global storage cannot be accessed
 pub fn cmd_global_add(args: GlobalKeyValueArgs) -> Result<()> {
     let dir = config::global_dir()?;
-    let mut map = read_env_map(&dir)?;
+    let key = load_key_for_dir(&dir)?;
+    let mut map = read_env_map_with_key(&dir, &key)?;
 
     let value = match args.value {
+        _ if args.typed.is_set() => typed::build_value(None, &args.typed)?,
         Some(v) => v,
         None => {
             use std::io::{self, Write};
@@ -445,8 +1029,10 @@ pub fn cmd_global_add(args: GlobalKeyValueArgs) -> Result<()> {
         }
     };
 
+    typed::validate_value(&value)?;
     map.insert(args.key.clone(), value);
-    write_env_map(&dir, &map)?;
+    write_env_map_with_key(&dir, &key, &map)?;
+    expiry::record_change(&dir, &key, &args.key)?;
     styles::success(format!("Added global credential '{}'", args.key));
     Ok(())
 }
@@ -482,12 +1068,22 @@ pub fn cmd_global_get(args: GlobalKeyArgs) -> Result<()> {
 /// a formatted header and total count. Global credentials are accessible from
 /// any project context and provide shared credential storage.
 ///
+/// # Arguments
+/// * `args` - Whether values are masked (the default), revealed or left out
+///
 /// # Returns
 /// * `Result<()>` - Success or error if global storage cannot be accessed
-pub fn cmd_global_list() -> Result<()> {
+pub fn cmd_global_list(args: RevealArgs) -> Result<()> {
     let dir = config::global_dir()?;
     let map = read_env_map(&dir)?;
+    let display = ValueDisplay::from_args(&args);
 
+    if output::is_structured() {
+        return output::emit(&CredentialsDoc {
+            project: "global",
+            credentials: display.doc(&map),
+        });
+    }
     if map.is_empty() {
         styles::info("🌍 No global credentials found");
         return Ok(());
@@ -496,9 +1092,10 @@ pub fn cmd_global_list() -> Result<()> {
     styles::header("🌍 Global Credentials");
     let count = map.len();
     for (k, v) in &map {
-        println!("{}={}", k, v);
+        println!("{}", display.line(k, v));
     }
     styles::info(format!("📊 Total: {} global credential(s)", count));
+    display.hint();
     Ok(())
 }
 
@@ -515,7 +1112,8 @@ pub fn cmd_global_list() -> Result<()> {
 /// * `Result<()>` - Success or error if key not found or deletion fails
 pub fn cmd_global_delete(args: GlobalKeyArgsForce) -> Result<()> {
     let dir = config::global_dir()?;
-    let mut map = read_env_map(&dir)?;
+    let key = load_key_for_dir(&dir)?;
+    let mut map = read_env_map_with_key(&dir, &key)?;
 
     if !map.contains_key(&args.key) {
         bail!("❌ Key '{}' not found in global storage", args.key);
@@ -548,7 +1146,8 @@ pub fn cmd_global_delete(args: GlobalKeyArgsForce) -> Result<()> {
         bail!("❌ Key '{}' not found in global storage", args.key);
     }
 
-    write_env_map(&dir, &map)?;
+    write_env_map_with_key(&dir, &key, &map)?;
+    expiry::forget_key(&dir, &key, &args.key)?;
     styles::success(format!("Deleted global credential '{}'", args.key));
     Ok(())
 }
@@ -566,7 +1165,8 @@ pub fn cmd_global_delete(args: GlobalKeyArgsForce) -> Result<()> {
 /// * `Result<()>` - Success or error if key doesn't exist or update fails
 pub fn cmd_global_update(args: GlobalKeyValueArgs) -> Result<()> {
     let dir = config::global_dir()?;
-    let mut map = read_env_map(&dir)?;
+    let key = load_key_for_dir(&dir)?;
+    let mut map = read_env_map_with_key(&dir, &key)?;
 
     // Check if key exists
     if !map.contains_key(&args.key) {
@@ -574,6 +1174,9 @@ pub fn cmd_global_update(args: GlobalKeyValueArgs) -> Result<()> {
     }
 
     let value = match args.value {
+        _ if args.typed.is_set() => {
+            typed::build_value(map.get(&args.key).map(String::as_str), &args.typed)?
+        }
         Some(v) => v,
         None => {
             use std::io::{self, Write};
@@ -585,8 +1188,10 @@ pub fn cmd_global_update(args: GlobalKeyValueArgs) -> Result<()> {
         }
     };
 
+    typed::validate_value(&value)?;
     map.insert(args.key.clone(), value);
-    write_env_map(&dir, &map)?;
+    write_env_map_with_key(&dir, &key, &map)?;
+    expiry::record_change(&dir, &key, &args.key)?;
     styles::success(format!("Updated global credential '{}'", args.key));
     Ok(())
 }
@@ -795,6 +1400,25 @@ pub fn cmd_delete_all(force: bool) -> Result<()> {
     Ok(())
 }
 
+/// `about --output json|yaml` document.
+#[derive(Serialize)]
+struct AboutDoc {
+    name: &'static str,
+    version: &'static str,
+    description: &'static str,
+    authors: &'static str,
+    repository: &'static str,
+    license: &'static str,
+    arch: &'static str,
+    os: &'static str,
+    gui_support: bool,
+    configured: bool,
+    base_dir: String,
+    total_projects: usize,
+    total_credentials: usize,
+    protections: hardening::Protections,
+}
+
 /// Show application information and details
 ///
 /// Displays comprehensive information about SafeHold including:
@@ -817,18 +1441,18 @@ pub fn cmd_about() -> Result<()> {
                 let mut credentials = 0;
 
                 // Count global credentials
-                if let Ok(global_dir) = config::global_dir() {
-                    if let Ok(map) = read_env_map(&global_dir) {
-                        credentials += map.len();
-                    }
+                if let Ok(global_dir) = config::global_dir()
+                    && let Ok(map) = read_env_map(&global_dir)
+                {
+                    credentials += map.len();
                 }
 
                 // Count project credentials
                 for project in &cfg.sets {
-                    if let Ok(project_dir) = config::set_dir(&project.id) {
-                        if let Ok(map) = read_env_map(&project_dir) {
-                            credentials += map.len();
-                        }
+                    if let Ok(project_dir) = config::set_dir(&project.id)
+                        && let Ok(map) = read_env_map(&project_dir)
+                    {
+                        credentials += map.len();
                     }
                 }
 
@@ -840,6 +1464,25 @@ pub fn cmd_about() -> Result<()> {
         (0, 0)
     };
 
+    if output::is_structured() {
+        return output::emit(&AboutDoc {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            description: env!("CARGO_PKG_DESCRIPTION"),
+            authors: env!("CARGO_PKG_AUTHORS"),
+            repository: env!("CARGO_PKG_REPOSITORY"),
+            license: env!("CARGO_PKG_LICENSE"),
+            arch: std::env::consts::ARCH,
+            os: std::env::consts::OS,
+            gui_support: cfg!(feature = "gui"),
+            configured: config_exists,
+            base_dir: base_dir.display().to_string(),
+            total_projects,
+            total_credentials,
+            protections: hardening::status(),
+        });
+    }
+
     println!();
     println!("┌─────────────────────────────────────────────────────────────────┐");
     println!("│  ███████╗ █████╗ ███████╗███████╗██╗  ██╗ ██████╗ ██╗     ██████╗ │");
@@ -896,6 +1539,7 @@ pub fn cmd_about() -> Result<()> {
     println!("   • Memory-safe Rust implementation");
     println!("   • Cross-platform secure key derivation");
     println!("   • No plaintext storage of sensitive data");
+    println!("   Protections: {}", hardening::status().summary());
     println!();
 
     println!("🚀 Available Features:");
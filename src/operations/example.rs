@@ -0,0 +1,123 @@
+//! `.env.example` templates generated from a project, and diffs against them
+//!
+//! `example` lists the variables `run` would inject (typed credentials
+//! flattened, schema defaults filled in) with empty placeholders, so the file
+//! can be committed. Schema defaults are written as-is since they are not
+//! secrets. `diff --against` compares a committed example with the vault by
+//! key name only.
+
+use crate::cli::cli::ProjectTargetArgs;
+use crate::cli::styles;
+use crate::operations::{envops, otp, schema, typed};
+use anyhow::{Context, Result, bail};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// A line of the generated example: placeholder value and optional comment.
+struct ExampleEntry {
+    value: String,
+    hint: Option<String>,
+}
+
+/// Variables the project provides, as `run` would inject them.
+fn provided_vars(dir: &Path) -> Result<BTreeMap<String, ExampleEntry>> {
+    let map = envops::read_env_map(dir)?;
+    let hints = typed::flatten_hints(&map);
+    let mut out: BTreeMap<String, ExampleEntry> = typed::flatten(&map)
+        .iter()
+        .map(|(key, value)| {
+            let hint = hints
+                .get(key)
+                .cloned()
+                .or_else(|| otp::is_otpauth(value).then(|| "otpauth://totp/... URI".to_string()));
+            (
+                key.clone(),
+                ExampleEntry {
+                    value: String::new(),
+                    hint,
+                },
+            )
+        })
+        .collect();
+    if let Some((schema, _)) = schema::resolve(dir, None, true)? {
+        for (key, rule) in &schema.keys {
+            if let Some(entry) = out.get_mut(key) {
+                entry.hint = rule.hint().or(entry.hint.take());
+            } else if let Some(default) = &rule.default {
+                out.insert(
+                    key.clone(),
+                    ExampleEntry {
+                        value: default.clone(),
+                        hint: rule.hint(),
+                    },
+                );
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Print a `.env.example` for a project: keys, placeholders and hints, no secrets.
+pub fn cmd_example(args: ProjectTargetArgs) -> Result<()> {
+    let dir = envops::resolve_set_dir(&args.project)?;
+    let vars = provided_vars(&dir)?;
+    let mut out = format!(
+        "# Generated by `safehold example -p {}`; fill in real values locally\n",
+        args.project
+    );
+    for (key, entry) in &vars {
+        if let Some(hint) = &entry.hint {
+            out.push_str(&format!("# {}\n", hint));
+        }
+        out.push_str(&format!("{}={}\n", key, entry.value));
+    }
+    print!("{}", out);
+    if vars.is_empty() {
+        styles::warn(format!("Project '{}' has no credentials", args.project));
+    }
+    Ok(())
+}
+
+/// Compare a project's keys with an example file; fails when they differ.
+pub fn diff_against(project: &str, against: &Path) -> Result<()> {
+    let dir = envops::resolve_set_dir(project)?;
+    let bytes = fs::read(against).with_context(|| format!("read {}", against.display()))?;
+    let expected: BTreeSet<String> = envops::read_env_map_from_bytes(&bytes)?
+        .keys()
+        .cloned()
+        .collect();
+    let provided: BTreeSet<String> = provided_vars(&dir)?.into_keys().collect();
+    let file = against.display();
+
+    let missing: Vec<&String> = expected.difference(&provided).collect();
+    let extra: Vec<&String> = provided.difference(&expected).collect();
+    if missing.is_empty() && extra.is_empty() {
+        styles::success(format!(
+            "Project '{}' and {} have the same {} key(s)",
+            project,
+            file,
+            expected.len()
+        ));
+        return Ok(());
+    }
+    styles::header(format!("🔍 {} vs {}", project, file));
+    if !missing.is_empty() {
+        styles::info(format!("Missing from project '{}':", project));
+        for key in &missing {
+            styles::bullet(format!("- {}", key));
+        }
+    }
+    if !extra.is_empty() {
+        styles::info(format!("Not in {}:", file));
+        for key in &extra {
+            styles::bullet(format!("+ {}", key));
+        }
+    }
+    bail!(
+        "{} key(s) missing from the project, {} not in {}",
+        missing.len(),
+        extra.len(),
+        file
+    )
+}
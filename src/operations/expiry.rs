@@ -0,0 +1,389 @@
+//! Credential expiry dates and rotation reminders
+//!
+//! Per-key `expires_at` and `rotate_every` settings, and the rotation and
+//! verification commands `rotate --save` remembers, live in `keys.enc` next to
+//! a project's data, encrypted with the project key like its values. `expiring`
+//! therefore checks the projects that open without a prompt and reports the
+//! rest as skipped.
+
+use crate::cli::cli::{ExpiringArgs, ExpiryArgs};
+use crate::cli::styles;
+use crate::core::config::key_meta_path;
+use crate::core::crypto;
+use crate::core::secret::SecretKey;
+use crate::operations::envops;
+use crate::utils::duration;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime};
+
+/// Expiry and rotation settings of one key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyMeta {
+    /// RFC 3339 timestamp after which the value is no longer valid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    /// Rotation interval such as `90d`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotate_every: Option<String>,
+    /// RFC 3339 timestamp of the last value change, the rotation clock's start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotated_at: Option<String>,
+    /// Shell command `rotate` runs to produce a new value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotate_command: Option<String>,
+    /// Plugin (`safehold-rotate-<name>` on PATH) `rotate` runs instead of a command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotate_plugin: Option<String>,
+    /// Shell command that must succeed with the new value before it is kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_command: Option<String>,
+}
+
+/// Why a key is due.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DueReason {
+    Expires,
+    Rotation,
+}
+
+impl KeyMeta {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.expires_at.is_none()
+            && self.rotate_every.is_none()
+            && self.rotate_command.is_none()
+            && self.rotate_plugin.is_none()
+            && self.verify_command.is_none()
+    }
+
+    /// The earlier of the expiry date and the next scheduled rotation.
+    pub fn due(&self) -> Option<(OffsetDateTime, DueReason)> {
+        let expires = self
+            .expires_at
+            .as_deref()
+            .and_then(|s| OffsetDateTime::parse(s, &Rfc3339).ok())
+            .map(|t| (t, DueReason::Expires));
+        let rotation = self.rotate_every.as_deref().and_then(|every| {
+            let every = duration::parse_duration(every).ok()?;
+            let from = OffsetDateTime::parse(self.rotated_at.as_deref()?, &Rfc3339).ok()?;
+            Some((add_duration(from, every)?, DueReason::Rotation))
+        });
+        match (expires, rotation) {
+            (Some(e), Some(r)) => Some(if r.0 < e.0 { r } else { e }),
+            (e, r) => e.or(r),
+        }
+    }
+}
+
+/// Settings of every key in a project that has any; `key` is the project key.
+pub fn load_meta(dir: &Path, key: &SecretKey) -> Result<BTreeMap<String, KeyMeta>> {
+    let path = key_meta_path(dir);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let enc = fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+    let pt = crypto::decrypt_with_key(key, &enc)?;
+    serde_json::from_slice(&pt).with_context(|| format!("parse {}", path.display()))
+}
+
+pub(crate) fn save_meta(
+    dir: &Path,
+    key: &SecretKey,
+    meta: &BTreeMap<String, KeyMeta>,
+) -> Result<()> {
+    let path = key_meta_path(dir);
+    if meta.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+        }
+    } else {
+        let ct = crypto::encrypt_with_key(key, &serde_json::to_vec(meta)?)?;
+        fs::write(&path, ct).with_context(|| format!("write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn now_rfc3339() -> String {
+    OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .unwrap_or_default()
+}
+
+/// Restart the rotation clock of `name` after its value changed.
+pub fn record_change(dir: &Path, key: &SecretKey, name: &str) -> Result<()> {
+    let mut meta = load_meta(dir, key)?;
+    if let Some(m) = meta.get_mut(name)
+        && m.rotate_every.is_some()
+    {
+        m.rotated_at = Some(now_rfc3339());
+        save_meta(dir, key, &meta)?;
+    }
+    Ok(())
+}
+
+/// Drop the settings of a deleted key.
+pub fn forget_key(dir: &Path, key: &SecretKey, name: &str) -> Result<()> {
+    let mut meta = load_meta(dir, key)?;
+    if meta.remove(name).is_some() {
+        save_meta(dir, key, &meta)?;
+    }
+    Ok(())
+}
+
+/// `2026-12-31`, an RFC 3339 timestamp, or a duration from now such as `90d`.
+fn parse_expiry(input: &str) -> Result<OffsetDateTime> {
+    if let Ok(t) = OffsetDateTime::parse(input, &Rfc3339) {
+        return Ok(t);
+    }
+    if let Ok(d) = Date::parse(input, format_description!("[year]-[month]-[day]")) {
+        return Ok(d.midnight().assume_utc());
+    }
+    match duration::parse_duration(input) {
+        Ok(d) => match add_duration(OffsetDateTime::now_utc(), d) {
+            Some(t) => Ok(t),
+            None => bail!("invalid expiry '{}': too far in the future", input),
+        },
+        Err(_) => bail!(
+            "invalid expiry '{}': expected a date (2026-12-31), an RFC 3339 timestamp or a duration (90d)",
+            input
+        ),
+    }
+}
+
+/// `t + d`, or `None` past the largest representable date.
+fn add_duration(t: OffsetDateTime, d: std::time::Duration) -> Option<OffsetDateTime> {
+    t.checked_add(time::Duration::try_from(d).ok()?)
+}
+
+fn format_date(t: OffsetDateTime) -> String {
+    t.format(format_description!("[year]-[month]-[day]"))
+        .unwrap_or_default()
+}
+
+/// "expired 3d ago" / "expires in 5d" style description of a due date.
+pub fn describe_due(due: OffsetDateTime, reason: DueReason, now: OffsetDateTime) -> String {
+    let what = match reason {
+        DueReason::Expires => ("expired", "expires in"),
+        DueReason::Rotation => ("rotation overdue by", "rotation due in"),
+    };
+    if due <= now {
+        let ago = duration::format_approx((now - due).try_into().unwrap_or_default());
+        match reason {
+            DueReason::Expires => format!("{} {} ago ({})", what.0, ago, format_date(due)),
+            DueReason::Rotation => format!("{} {} ({})", what.0, ago, format_date(due)),
+        }
+    } else {
+        let left = duration::format_approx((due - now).try_into().unwrap_or_default());
+        format!("{} {} ({})", what.1, left, format_date(due))
+    }
+}
+
+/// A key due within the requested window.
+#[derive(Debug, Clone)]
+pub struct DueKey {
+    pub project: String,
+    pub key: String,
+    pub due: OffsetDateTime,
+    pub reason: DueReason,
+}
+
+/// Keys across all projects (or one) that are due before `now + within`.
+///
+/// Projects are chosen like `envops::readable_env_maps`; also returns how
+/// many locked projects were skipped.
+pub fn due_within(within: std::time::Duration, only: Option<&str>) -> Result<(Vec<DueKey>, usize)> {
+    let Some(limit) = add_duration(OffsetDateTime::now_utc(), within) else {
+        bail!("--within is too far in the future");
+    };
+    let (projects, skipped) = envops::readable_project_keys(only)?;
+    let mut due = Vec::new();
+    for (project, dir, project_key) in projects {
+        due.extend(due_in_project(&project, &dir, &project_key, limit)?);
+    }
+    due.sort_by_key(|d| d.due);
+    Ok((due, skipped))
+}
+
+/// Keys of one project, labelled `project`, that are due by `limit`.
+pub fn due_in_project(
+    project: &str,
+    dir: &Path,
+    key: &SecretKey,
+    limit: OffsetDateTime,
+) -> Result<Vec<DueKey>> {
+    let mut due = Vec::new();
+    for (name, meta) in load_meta(dir, key)? {
+        if let Some((when, reason)) = meta.due()
+            && when <= limit
+        {
+            due.push(DueKey {
+                project: project.to_string(),
+                key: name,
+                due: when,
+                reason,
+            });
+        }
+    }
+    Ok(due)
+}
+
+/// Warn about keys `run` is about to inject that are expired or overdue.
+pub fn warn_due<'a>(
+    dir: &Path,
+    key: &SecretKey,
+    label: &str,
+    keys: impl Iterator<Item = &'a String>,
+) {
+    let Ok(meta) = load_meta(dir, key) else {
+        return;
+    };
+    let now = OffsetDateTime::now_utc();
+    for key in keys {
+        if let Some((when, reason)) = meta.get(key).and_then(KeyMeta::due)
+            && when <= now
+        {
+            styles::warn(format!(
+                "⏳ {} ({}): {}",
+                key,
+                label,
+                describe_due(when, reason, now)
+            ));
+        }
+    }
+}
+
+/// Show or change a key's expiry and rotation settings.
+pub fn cmd_expiry(args: ExpiryArgs) -> Result<()> {
+    let dir = envops::resolve_set_dir(&args.project)?;
+    let key = envops::load_key_for_dir(&dir)?;
+    let map = envops::read_env_map_with_key(&dir, &key)?;
+    if !map.contains_key(&args.key) {
+        bail!("key '{}' not found in project '{}'", args.key, args.project);
+    }
+    let mut meta = load_meta(&dir, &key)?;
+    let mut entry = meta.get(&args.key).cloned().unwrap_or_default();
+    if args.clear {
+        // Rotation commands set up with `rotate --save` stay
+        entry.expires_at = None;
+        entry.rotate_every = None;
+    }
+    let changed =
+        args.clear || args.expires_at.is_some() || args.rotate_every.is_some() || args.rotated;
+    if let Some(expires) = &args.expires_at {
+        entry.expires_at = match expires.as_str() {
+            "never" | "" => None,
+            s => Some(parse_expiry(s)?.format(&Rfc3339)?),
+        };
+    }
+    if let Some(every) = args.rotate_every {
+        if add_duration(OffsetDateTime::now_utc(), every).is_none() {
+            bail!("--rotate-every is too long");
+        }
+        entry.rotate_every = (!every.is_zero()).then(|| duration::format_duration(every));
+        if entry.rotated_at.is_none() {
+            entry.rotated_at = Some(now_rfc3339());
+        }
+    }
+    if args.rotated {
+        if entry.rotate_every.is_none() {
+            bail!("--rotated needs a rotation interval; set one with --rotate-every");
+        }
+        entry.rotated_at = Some(now_rfc3339());
+    }
+    if entry.rotate_every.is_none() {
+        entry.rotated_at = None;
+    }
+    if changed {
+        if entry.is_empty() {
+            meta.remove(&args.key);
+        } else {
+            meta.insert(args.key.clone(), entry.clone());
+        }
+        save_meta(&dir, &key, &meta)?;
+        styles::success(format!("⏳ Expiry settings updated for '{}'", args.key));
+    }
+
+    let now = OffsetDateTime::now_utc();
+    styles::header(format!("⏳ {} ({})", args.key, args.project));
+    styles::kv(
+        "Expires",
+        entry
+            .expires_at
+            .as_deref()
+            .and_then(|s| OffsetDateTime::parse(s, &Rfc3339).ok())
+            .map(|t| describe_due(t, DueReason::Expires, now))
+            .unwrap_or_else(|| "never".to_string()),
+    );
+    styles::kv(
+        "Rotate every",
+        entry.rotate_every.as_deref().unwrap_or("not scheduled"),
+    );
+    if let Some((when, DueReason::Rotation)) = entry.due() {
+        styles::kv(
+            "Next rotation",
+            describe_due(when, DueReason::Rotation, now),
+        );
+    }
+    Ok(())
+}
+
+/// List keys that expire or are due for rotation soon; fails when any are.
+pub fn cmd_expiring(args: ExpiringArgs) -> Result<()> {
+    let (due, skipped) = due_within(args.within, args.project.as_deref())?;
+    let window = duration::format_duration(args.within);
+    if skipped > 0 {
+        styles::info(format!(
+            "🔒 Skipped {} locked project(s); pass --password-file, set SAFEHOLD_PASSWORD or use --project to include them",
+            skipped
+        ));
+    }
+    if due.is_empty() {
+        styles::success(format!(
+            "No credentials expire or need rotation within {}",
+            window
+        ));
+        return Ok(());
+    }
+    let now = OffsetDateTime::now_utc();
+    styles::header(format!("⏳ Credentials due within {}", window));
+    for d in &due {
+        styles::bullet(format!(
+            "{} / {}: {}",
+            d.project,
+            d.key,
+            describe_due(d.due, d.reason, now)
+        ));
+    }
+    bail!("{} credential(s) due within {}", due.len(), window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    #[test]
+    fn due_is_earliest_of_expiry_and_rotation() {
+        let now = OffsetDateTime::now_utc();
+        let meta = KeyMeta {
+            expires_at: Some((now + Duration::days(30)).format(&Rfc3339).unwrap()),
+            rotate_every: Some("7d".to_string()),
+            rotated_at: Some((now - Duration::days(10)).format(&Rfc3339).unwrap()),
+            ..Default::default()
+        };
+        let (when, reason) = meta.due().unwrap();
+        assert_eq!(reason, DueReason::Rotation);
+        assert!(when < now);
+        assert!(describe_due(when, reason, now).starts_with("rotation overdue by 3d"));
+
+        let expiry = parse_expiry("2030-01-02").unwrap();
+        assert_eq!(format_date(expiry), "2030-01-02");
+        assert!(parse_expiry("soon").is_err());
+        assert!(parse_expiry("99999999999w").is_err());
+    }
+}
@@ -0,0 +1,383 @@
+//! Random secret generation for `generate` and per-project generation policies
+//!
+//! Values are drawn from the same thread-local CSPRNG (`rand::rng`) that
+//! `crypto` uses for keys, salts and nonces, sampled uniformly from the chosen
+//! alphabet. Policies live next to a project's data in `policy.json`.
+
+use crate::cli::cli::{GenerateArgs, PolicyArgs};
+use crate::cli::styles;
+use crate::core::config::policy_path;
+use crate::operations::{envops, expiry};
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use rand::{Rng, rng};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Built-in passphrase word list: 1024 short words, 10 bits of entropy each.
+const WORDLIST: &str = include_str!("wordlist.txt");
+
+/// Default length in characters, or in words for `Charset::Words`.
+const DEFAULT_LENGTH: usize = 32;
+const DEFAULT_WORDS: usize = 8;
+
+/// Attempts at drawing a value that satisfies the required character classes.
+const MAX_ATTEMPTS: usize = 1000;
+
+/// Alphabet a generated value is drawn from.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Charset {
+    /// Letters and digits
+    Alnum,
+    /// Lowercase hexadecimal
+    Hex,
+    /// Standard base64 alphabet (A-Z a-z 0-9 + /)
+    Base64,
+    /// URL-safe base64 alphabet (A-Z a-z 0-9 - _)
+    Urlsafe,
+    /// Dash-separated words from the built-in list
+    Words,
+}
+
+impl Charset {
+    fn alphabet(self) -> &'static [u8] {
+        match self {
+            Charset::Alnum => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            Charset::Hex => b"0123456789abcdef",
+            Charset::Base64 => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+            Charset::Urlsafe => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+            // Lowercase words joined by '-'
+            Charset::Words => b"abcdefghijklmnopqrstuvwxyz-",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Charset::Alnum => "alnum",
+            Charset::Hex => "hex",
+            Charset::Base64 => "base64",
+            Charset::Urlsafe => "urlsafe",
+            Charset::Words => "words",
+        }
+    }
+}
+
+/// Character class a policy can require.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CharClass {
+    Lower,
+    Upper,
+    Digit,
+    Symbol,
+}
+
+impl CharClass {
+    fn matches(self, b: u8) -> bool {
+        match self {
+            CharClass::Lower => b.is_ascii_lowercase(),
+            CharClass::Upper => b.is_ascii_uppercase(),
+            CharClass::Digit => b.is_ascii_digit(),
+            CharClass::Symbol => b.is_ascii_punctuation(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            CharClass::Lower => "lower",
+            CharClass::Upper => "upper",
+            CharClass::Digit => "digit",
+            CharClass::Symbol => "symbol",
+        }
+    }
+}
+
+/// Per-project rules every generated value must satisfy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Policy {
+    /// Minimum length in characters
+    #[serde(default)]
+    pub min_length: Option<usize>,
+    /// Character classes that must each appear at least once
+    #[serde(default)]
+    pub require: Vec<CharClass>,
+}
+
+impl Policy {
+    fn is_empty(&self) -> bool {
+        self.min_length.is_none() && self.require.is_empty()
+    }
+}
+
+/// Read a project's policy; projects without one get the empty policy.
+pub fn load_policy(dir: &Path) -> Result<Policy> {
+    let path = policy_path(dir);
+    if !path.exists() {
+        return Ok(Policy::default());
+    }
+    let data = fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_slice(&data).with_context(|| format!("parse {}", path.display()))
+}
+
+fn save_policy(dir: &Path, policy: &Policy) -> Result<()> {
+    let path = policy_path(dir);
+    if policy.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+        }
+        return Ok(());
+    }
+    fs::write(&path, serde_json::to_vec_pretty(policy)?)
+        .with_context(|| format!("write {}", path.display()))
+}
+
+/// Generate a random value; `length` counts words for `Charset::Words`.
+pub fn generate_secret(charset: Charset, length: Option<usize>, policy: &Policy) -> Result<String> {
+    let length = length.unwrap_or(match charset {
+        Charset::Words => DEFAULT_WORDS,
+        _ => DEFAULT_LENGTH,
+    });
+    if length == 0 {
+        bail!("length must be at least 1");
+    }
+    for class in &policy.require {
+        if !charset.alphabet().iter().any(|&b| class.matches(b)) {
+            bail!(
+                "policy requires {} characters, which the {} charset cannot produce",
+                class.name(),
+                charset.name()
+            );
+        }
+    }
+    if charset == Charset::Words {
+        // Every phrase is at least this long, so the policy holds for any draw
+        let shortest = WORDLIST.lines().map(str::len).min().unwrap_or(1);
+        let min_chars = shortest * length + (length - 1);
+        if let Some(min) = policy.min_length
+            && min_chars < min
+        {
+            bail!(
+                "policy requires at least {} characters, but {} words can be as short as {}; use --length {} or more",
+                min,
+                length,
+                min_chars,
+                (min + 1).div_ceil(shortest + 1)
+            );
+        }
+        // Words are lowercase, so only the '-' separator supplies anything else
+        if length < 2 && policy.require.contains(&CharClass::Symbol) {
+            bail!(
+                "policy requires a symbol, which a single word cannot contain; use --length 2 or more"
+            );
+        }
+    } else {
+        if let Some(min) = policy.min_length
+            && length < min
+        {
+            bail!(
+                "policy requires at least {} characters, got --length {}",
+                min,
+                length
+            );
+        }
+        if length < policy.require.len() {
+            bail!(
+                "--length {} is too short to include all {} required character classes",
+                length,
+                policy.require.len()
+            );
+        }
+    }
+
+    let mut rng = rng();
+    for _ in 0..MAX_ATTEMPTS {
+        let value = match charset {
+            Charset::Words => {
+                let words: Vec<&str> = WORDLIST.lines().collect();
+                (0..length)
+                    .map(|_| words[rng.random_range(0..words.len())])
+                    .collect::<Vec<_>>()
+                    .join("-")
+            }
+            _ => {
+                let alphabet = charset.alphabet();
+                (0..length)
+                    .map(|_| alphabet[rng.random_range(0..alphabet.len())] as char)
+                    .collect()
+            }
+        };
+        // Resample rather than patch in characters, keeping the draw uniform
+        let long_enough = policy.min_length.is_none_or(|min| value.len() >= min);
+        if long_enough
+            && policy
+                .require
+                .iter()
+                .all(|class| value.bytes().any(|b| class.matches(b)))
+        {
+            return Ok(value);
+        }
+    }
+    bail!("could not satisfy the project policy; try a larger --length")
+}
+
+/// Generate a value and store it under a key without printing it.
+pub fn cmd_generate(args: GenerateArgs) -> Result<()> {
+    let dir = envops::resolve_set_dir(&args.project)?;
+    let policy = load_policy(&dir)?;
+    let key = envops::load_key_for_dir(&dir)?;
+    let mut map = envops::read_env_map_with_key(&dir, &key)?;
+    if map.contains_key(&args.key) && !args.force {
+        bail!(
+            "key '{}' already exists in project '{}'; use --force to replace it",
+            args.key,
+            args.project
+        );
+    }
+    let value = generate_secret(args.charset, args.length, &policy)?;
+    let chars = value.len();
+    map.insert(args.key.clone(), value);
+    envops::write_env_map_with_key(&dir, &key, &map)?;
+    expiry::record_change(&dir, &key, &args.key)?;
+    styles::success(format!(
+        "🎲 Stored a new {}-character {} value in {} (project '{}'); it was not displayed",
+        chars,
+        args.charset.name(),
+        args.key,
+        args.project
+    ));
+    Ok(())
+}
+
+/// Show or change a project's generation policy.
+pub fn cmd_policy(args: PolicyArgs) -> Result<()> {
+    let dir = envops::resolve_set_dir(&args.project)?;
+    let mut policy = if args.clear {
+        Policy::default()
+    } else {
+        load_policy(&dir)?
+    };
+    let changed = args.clear || args.min_length.is_some() || !args.require.is_empty();
+    if let Some(min) = args.min_length {
+        policy.min_length = (min > 0).then_some(min);
+    }
+    if !args.require.is_empty() {
+        policy.require = args.require;
+        policy.require.sort();
+        policy.require.dedup();
+    }
+    if changed {
+        save_policy(&dir, &policy)?;
+        styles::success(format!("📏 Policy updated for project '{}'", args.project));
+    }
+
+    styles::header(format!("📏 Generation policy: {}", args.project));
+    styles::kv(
+        "Minimum length",
+        policy
+            .min_length
+            .map(|m| m.to_string())
+            .unwrap_or_else(|| "none".to_string()),
+    );
+    styles::kv(
+        "Required classes",
+        if policy.require.is_empty() {
+            "none".to_string()
+        } else {
+            policy
+                .require
+                .iter()
+                .map(|c| c.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        },
+    );
+    Ok(())
+}
+
+/// Policy of a project addressed the way the GUI does (set id or `global`).
+#[cfg(feature = "gui")]
+pub fn policy_for(id_or_global: &str) -> Result<Policy> {
+    let dir = if id_or_global == "global" {
+        crate::core::config::global_dir()?
+    } else {
+        crate::core::config::set_dir(id_or_global)?
+    };
+    load_policy(&dir)
+}
+
+/// Generate a value the GUI can fill in without asking for a charset: letters
+/// and digits unless the policy wants symbols, at least 32 characters.
+#[cfg(feature = "gui")]
+pub fn generate_for_policy(policy: &Policy) -> Result<String> {
+    let charset = if policy.require.contains(&CharClass::Symbol) {
+        Charset::Base64
+    } else {
+        Charset::Alnum
+    };
+    let length = policy.min_length.unwrap_or(0).max(DEFAULT_LENGTH);
+    generate_secret(charset, Some(length), policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wordlist_is_unique_lowercase_and_power_of_two() {
+        let words: Vec<&str> = WORDLIST.lines().collect();
+        assert_eq!(words.len(), 1024);
+        let mut sorted = words.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), words.len());
+        assert!(
+            words
+                .iter()
+                .all(|w| w.bytes().all(|b| b.is_ascii_lowercase()))
+        );
+    }
+
+    #[test]
+    fn generates_from_charset_and_honours_policy() {
+        let policy = Policy {
+            min_length: Some(16),
+            require: vec![CharClass::Upper, CharClass::Digit],
+        };
+        let value = generate_secret(Charset::Alnum, Some(20), &policy).unwrap();
+        assert_eq!(value.len(), 20);
+        assert!(value.bytes().any(|b| b.is_ascii_uppercase()));
+        assert!(value.bytes().any(|b| b.is_ascii_digit()));
+
+        let hex = generate_secret(Charset::Hex, None, &Policy::default()).unwrap();
+        assert!(hex.len() == 32 && hex.bytes().all(|b| b.is_ascii_hexdigit()));
+
+        let words = generate_secret(Charset::Words, Some(4), &Policy::default()).unwrap();
+        assert_eq!(words.split('-').count(), 4);
+
+        assert!(generate_secret(Charset::Alnum, Some(8), &policy).is_err());
+        assert!(generate_secret(Charset::Hex, Some(32), &policy).is_err());
+
+        // Word phrases are checked up front instead of resampled
+        let long = Policy {
+            min_length: Some(40),
+            require: vec![CharClass::Symbol],
+        };
+        let err = generate_secret(Charset::Words, Some(4), &long).unwrap_err();
+        assert!(err.to_string().contains("use --length 11 or more"));
+        let phrase = generate_secret(Charset::Words, Some(11), &long).unwrap();
+        assert!(phrase.len() >= 40);
+        assert!(
+            generate_secret(
+                Charset::Words,
+                Some(1),
+                &Policy {
+                    min_length: None,
+                    require: vec![CharClass::Symbol],
+                }
+            )
+            .is_err()
+        );
+    }
+}
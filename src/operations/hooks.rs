@@ -0,0 +1,282 @@
+//! Git hooks that keep secrets out of commits
+//!
+//! `hooks install` writes a small `pre-commit` or `pre-push` script bound to a
+//! project. The script calls back into `safehold hooks run`, which rejects
+//! `.env*` files and any file containing one of the project's values, naming
+//! the leaked key but never printing the secret itself.
+
+use crate::cli::cli::{HookKind, HooksAction};
+use crate::cli::styles;
+use crate::operations::envops::{self, CLEAN_TEMPLATE_FILES};
+use crate::operations::scan::{self, Detector, Finding};
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+/// Marks hook scripts written by SafeHold so they can be replaced or removed.
+const HOOK_MARKER: &str = "# safehold-hook";
+
+impl HookKind {
+    fn file_name(self) -> &'static str {
+        match self {
+            HookKind::PreCommit => "pre-commit",
+            HookKind::PrePush => "pre-push",
+        }
+    }
+}
+
+/// Dispatch `safehold hooks <action>`.
+pub fn cmd_hooks(action: HooksAction) -> Result<()> {
+    match action {
+        HooksAction::Install {
+            project,
+            pre_push,
+            force,
+        } => install(&project, hook_kind(pre_push), force),
+        HooksAction::Uninstall { pre_push } => uninstall(hook_kind(pre_push)),
+        HooksAction::Run { hook, project } => run(hook, &project),
+    }
+}
+
+fn hook_kind(pre_push: bool) -> HookKind {
+    if pre_push {
+        HookKind::PrePush
+    } else {
+        HookKind::PreCommit
+    }
+}
+
+/// Path of `kind` in the current repository, honouring `core.hooksPath`.
+fn hook_path(kind: HookKind) -> Result<PathBuf> {
+    let out = scan::git(
+        Path::new("."),
+        &["rev-parse", "--path-format=absolute", "--git-path", "hooks"],
+    )?;
+    let dir = PathBuf::from(String::from_utf8_lossy(&out).trim());
+    Ok(dir.join(kind.file_name()))
+}
+
+fn install(project: &str, kind: HookKind, force: bool) -> Result<()> {
+    // Fail early on unknown projects rather than on the first commit
+    let cfg = crate::core::config::load_config()?;
+    if project != "global"
+        && !cfg
+            .sets
+            .iter()
+            .any(|s| s.id == project || s.name == project)
+    {
+        bail!("project not found: {}", project);
+    }
+
+    let path = hook_path(kind)?;
+    if let Ok(existing) = fs::read_to_string(&path)
+        && !existing.contains(HOOK_MARKER)
+    {
+        if !force {
+            bail!(
+                "{} already exists and was not installed by SafeHold; use --force to replace it (a .bak copy is kept)",
+                path.display()
+            );
+        }
+        let backup = path.with_extension("bak");
+        fs::copy(&path, &backup).with_context(|| format!("back up {}", path.display()))?;
+        styles::info(format!("💾 Existing hook saved as {}", backup.display()));
+    }
+
+    let exe = std::env::current_exe().context("locate safehold executable")?;
+    let script = format!(
+        "#!/bin/sh\n{}: installed by `safehold hooks install`, remove with `safehold hooks uninstall`\nexec {} hooks run {} --project {}\n",
+        HOOK_MARKER,
+        shell_quote(&exe.to_string_lossy()),
+        kind.file_name(),
+        shell_quote(project)
+    );
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    }
+    fs::write(&path, script).with_context(|| format!("write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    }
+    styles::success(format!(
+        "🪝 Installed {} hook for project '{}': {}",
+        kind.file_name(),
+        project,
+        path.display()
+    ));
+    Ok(())
+}
+
+fn uninstall(kind: HookKind) -> Result<()> {
+    let path = hook_path(kind)?;
+    match fs::read_to_string(&path) {
+        Ok(existing) if existing.contains(HOOK_MARKER) => {
+            fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+            styles::success(format!("🗑️ Removed {} hook", kind.file_name()));
+        }
+        Ok(_) => bail!(
+            "{} was not installed by SafeHold; leaving it in place",
+            path.display()
+        ),
+        Err(_) => styles::info(format!("No {} hook installed", kind.file_name())),
+    }
+    Ok(())
+}
+
+/// Body of the installed hook: check what is about to be committed or pushed.
+fn run(kind: HookKind, project: &str) -> Result<()> {
+    let root = scan::git_root(Path::new("."))?;
+    let (maps, _) = envops::readable_env_maps(Some(project))?;
+    let detector = Detector::vault_only(scan::vault_secrets(maps));
+
+    let mut env_files = Vec::new();
+    let mut findings = Vec::new();
+    let mut check = |shown: String, path: &str, blob: &str| -> Result<()> {
+        if is_env_file(path) {
+            env_files.push(shown);
+            return Ok(());
+        }
+        let bytes = scan::git(&root, &["cat-file", "blob", blob])?;
+        if let Some(text) = scan::text_contents(&bytes) {
+            detector.scan_text(&shown, &text, &mut findings);
+        }
+        Ok(())
+    };
+
+    match kind {
+        HookKind::PreCommit => {
+            for path in scan::staged_files(&root)? {
+                check(path.clone(), &path, &format!(":{}", path))?;
+            }
+        }
+        HookKind::PrePush => {
+            for commit in pushed_commits(&root)? {
+                // `-m` diffs a merge against each parent, so content written
+                // while resolving it is checked too; a path may then repeat
+                let out = scan::git(
+                    &root,
+                    &[
+                        "diff-tree",
+                        "--root",
+                        "-m",
+                        "--no-commit-id",
+                        "--name-only",
+                        "-r",
+                        "--diff-filter=ACMR",
+                        "-z",
+                        &commit,
+                    ],
+                )?;
+                let mut paths: Vec<String> = out
+                    .split(|&b| b == 0)
+                    .filter(|p| !p.is_empty())
+                    .map(|p| String::from_utf8_lossy(p).into_owned())
+                    .collect();
+                paths.sort();
+                paths.dedup();
+                for path in paths {
+                    check(
+                        format!("{}:{}", &commit[..commit.len().min(8)], path),
+                        &path,
+                        &format!("{}:{}", commit, path),
+                    )?;
+                }
+            }
+        }
+    }
+
+    if env_files.is_empty() && findings.is_empty() {
+        return Ok(());
+    }
+    report(kind, &env_files, &findings);
+    bail!(
+        "{} blocked by SafeHold: {} issue(s)",
+        kind.file_name(),
+        env_files.len() + findings.len()
+    )
+}
+
+/// Commits that the push introduces, read from the pre-push hook's stdin.
+fn pushed_commits(root: &Path) -> Result<Vec<String>> {
+    let mut commits = Vec::new();
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [_, local_oid, _, remote_oid] = fields[..] else {
+            continue;
+        };
+        if is_zero_oid(local_oid) {
+            // Branch deletion pushes no content
+            continue;
+        }
+        // A remote tip we never fetched cannot bound the range; fall back to
+        // everything not already on a remote-tracking branch
+        let known_remote = !is_zero_oid(remote_oid)
+            && scan::git(
+                root,
+                &["cat-file", "-e", &format!("{}^{{commit}}", remote_oid)],
+            )
+            .is_ok();
+        let range = if !known_remote {
+            vec![local_oid.to_string(), "--not".into(), "--remotes".into()]
+        } else {
+            vec![format!("{}..{}", remote_oid, local_oid)]
+        };
+        let mut args = vec!["rev-list"];
+        args.extend(range.iter().map(String::as_str));
+        let out = scan::git(root, &args)?;
+        for oid in String::from_utf8_lossy(&out).lines() {
+            if !commits.iter().any(|c| c == oid) {
+                commits.push(oid.to_string());
+            }
+        }
+    }
+    Ok(commits)
+}
+
+/// The all-zero object name git uses for "no commit" (SHA-1 or SHA-256).
+fn is_zero_oid(oid: &str) -> bool {
+    oid.bytes().all(|b| b == b'0')
+}
+
+/// `.env`, `.env.local`, ... but not committed templates such as `.env.example`.
+fn is_env_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    (name == ".env" || name.starts_with(".env.")) && !CLEAN_TEMPLATE_FILES.contains(&name)
+}
+
+fn report(kind: HookKind, env_files: &[String], findings: &[Finding]) {
+    let action = match kind {
+        HookKind::PreCommit => "Commit",
+        HookKind::PrePush => "Push",
+    };
+    styles::error(format!("🚫 {} blocked by SafeHold", action));
+    for file in env_files {
+        styles::error(format!(
+            "  {}: plaintext env file; keep it in SafeHold and use `safehold run` or `export --temp`",
+            file
+        ));
+    }
+    for f in findings {
+        styles::error(format!(
+            "  {}:{}:{}: {}",
+            f.path, f.line, f.column, f.message
+        ));
+    }
+    styles::info(match kind {
+        HookKind::PreCommit => {
+            "💡 Remove the secret (or unstage the file) and commit again; --no-verify skips this check"
+        }
+        HookKind::PrePush => {
+            "💡 Rewrite the offending commits to drop the secret and rotate it; --no-verify skips this check"
+        }
+    });
+}
+
+/// Single-quote `s` for POSIX sh.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
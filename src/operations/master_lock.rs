@@ -3,9 +3,10 @@
 //! When Global Master Lock is enabled, ALL projects (including individual projects and global)
 //! require the same master password for access. This provides unified security across all credentials.
 
-use crate::cli::styles;
+use crate::cli::{output, styles};
 use crate::core::config;
 use crate::core::crypto;
+use crate::core::secret::SecretString;
 use crate::utils::app_settings;
 use anyhow::{Result, bail};
 use rpassword;
@@ -126,11 +127,11 @@ pub fn verify_master_password(password: &str) -> Result<bool> {
         .password_hash
         .ok_or_else(|| anyhow::anyhow!("Master lock enabled but no password hash found"))?;
 
-    Ok(crypto::argon2_verify(password.as_bytes(), &stored_hash)?)
+    crypto::argon2_verify(password.as_bytes(), &stored_hash)
 }
 
 /// Prompt for master password if master lock is enabled
-pub fn prompt_master_password_if_needed() -> Result<Option<String>> {
+pub fn prompt_master_password_if_needed() -> Result<Option<SecretString>> {
     if !is_master_lock_enabled() {
         return Ok(None);
     }
@@ -138,9 +139,9 @@ pub fn prompt_master_password_if_needed() -> Result<Option<String>> {
     styles::info("🔒 Global Master Lock is enabled");
 
     loop {
-        let password = rpassword::prompt_password("Enter master password: ")?;
+        let password = SecretString::from(rpassword::prompt_password("Enter master password: ")?);
 
-        if verify_master_password(&password)? {
+        if verify_master_password(password.expose())? {
             styles::success("✅ Master password verified");
             return Ok(Some(password));
         } else {
@@ -152,7 +153,7 @@ pub fn prompt_master_password_if_needed() -> Result<Option<String>> {
 /// Check if access is allowed to any project operations
 /// Returns the master password if master lock is enabled and verified
 #[allow(dead_code)]
-pub fn check_master_access() -> Result<Option<String>> {
+pub fn check_master_access() -> Result<Option<SecretString>> {
     prompt_master_password_if_needed()
 }
 
@@ -163,6 +164,10 @@ pub fn display_master_lock_status() -> Result<()> {
     // Initialize app settings if they don't exist
     let _app_settings = app_settings::load_settings()?;
 
+    if output::is_structured() {
+        return output::emit(&serde_json::json!({ "enabled": info.enabled }));
+    }
+
     styles::header("Global Master Lock Status");
     styles::divider();
 
@@ -195,18 +200,20 @@ pub fn cmd_master_lock(enable: Option<bool>) -> Result<()> {
             styles::info("Setting up Global Master Lock...");
             styles::warn("⚠️  This will require a master password for ALL project access");
 
-            let password = rpassword::prompt_password("Create master password: ")?;
-            let confirm = rpassword::prompt_password("Confirm master password: ")?;
+            let password =
+                SecretString::from(rpassword::prompt_password("Create master password: ")?);
+            let confirm =
+                SecretString::from(rpassword::prompt_password("Confirm master password: ")?);
 
             if password != confirm {
                 bail!("Passwords do not match");
             }
 
-            if password.len() < 8 {
+            if password.expose().len() < 8 {
                 bail!("Master password must be at least 8 characters long");
             }
 
-            enable_master_lock(&password)?;
+            enable_master_lock(password.expose())?;
         }
         Some(false) => {
             if !is_master_lock_enabled() {
@@ -215,7 +222,7 @@ pub fn cmd_master_lock(enable: Option<bool>) -> Result<()> {
             }
 
             // Verify current master password before disabling
-            if let Some(_) = prompt_master_password_if_needed()? {
+            if prompt_master_password_if_needed()?.is_some() {
                 disable_master_lock()?;
             } else {
                 bail!("Master password verification required to disable");
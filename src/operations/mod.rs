@@ -2,7 +2,32 @@
 //!
 //! This module contains the main business logic operations:
 //! - Environment variable operations (CRUD, export, run)
+//! - Random secret generation and per-project policies
+//! - Expiry dates, rotation reminders and scripted rotation with history
+//! - One-time password codes for `otpauth://` credentials
+//! - Typed credentials (login, API key, certificate, SSH key, database)
+//! - Validation schemas enforced by `check` and `run`/`export --strict`
+//! - `.env.example` generation and diffing
+//! - Comparing and copying credentials between projects
 //! - Master lock functionality for unified password management
+//! - Non-interactive password sources (files, descriptors, helper commands)
+//! - Child process supervision and output redaction for `run`
+//! - Private temporary files for secrets that tools expect as paths
+//! - Scanning files for leaked secrets and git hooks that block them
 
 pub mod envops;
+pub mod example;
+pub mod expiry;
+pub mod generate;
+pub mod hooks;
 pub mod master_lock;
+pub mod otp;
+pub mod passwords;
+pub mod process;
+pub mod redact;
+pub mod rotate;
+pub mod scan;
+pub mod schema;
+pub mod secret_files;
+pub mod sync;
+pub mod typed;
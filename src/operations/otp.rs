@@ -0,0 +1,273 @@
+//! One-time password credentials (`otpauth://` URIs) and `safehold otp`
+//!
+//! A value starting with `otpauth://` is a TOTP seed rather than a plain
+//! string: `add` and `update` validate it, `otp` prints the current RFC 6238
+//! code, and the GUI shows that code with a countdown. The seed itself is only
+//! ever shown by `get`, like any other value.
+//!
+//! The URI itself is the representation; there is no `otp` typed credential,
+//! so authenticator exports and QR codes can be stored unchanged.
+
+use crate::cli::cli::ProjectKeyArgs;
+use crate::cli::styles;
+use crate::operations::envops;
+use anyhow::{Result, bail};
+use hmac::{Hmac, Mac};
+use percent_encoding::percent_decode_str;
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::io::IsTerminal;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zeroize::Zeroizing;
+
+const OTPAUTH_SCHEME: &str = "otpauth://";
+
+/// HMAC hash named by the URI's `algorithm` parameter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// A parsed `otpauth://totp/...` URI.
+#[derive(Clone)]
+pub struct Totp {
+    secret: Zeroizing<Vec<u8>>,
+    algorithm: Algorithm,
+    digits: u32,
+    period: u64,
+    /// `Issuer:account` label from the URI path, for display
+    pub label: String,
+}
+
+impl std::fmt::Debug for Totp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Totp")
+            .field("algorithm", &self.algorithm)
+            .field("digits", &self.digits)
+            .field("period", &self.period)
+            .field("label", &self.label)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether a stored value is an `otpauth://` URI.
+pub fn is_otpauth(value: &str) -> bool {
+    value
+        .get(..OTPAUTH_SCHEME.len())
+        .is_some_and(|s| s.eq_ignore_ascii_case(OTPAUTH_SCHEME))
+}
+
+/// Reject malformed `otpauth://` values before they are stored.
+pub fn validate_value(value: &str) -> Result<()> {
+    if is_otpauth(value) {
+        Totp::from_uri(value)?;
+    }
+    Ok(())
+}
+
+impl Totp {
+    pub fn from_uri(uri: &str) -> Result<Self> {
+        let url = match url::Url::parse(uri.trim()) {
+            Ok(u) if u.scheme() == "otpauth" => u,
+            _ => bail!("invalid otpauth URI: expected otpauth://totp/LABEL?secret=..."),
+        };
+        match url.host_str().map(str::to_ascii_lowercase).as_deref() {
+            Some("totp") => {}
+            Some("hotp") => bail!("otpauth://hotp (counter-based) codes are not supported"),
+            _ => bail!("invalid otpauth URI: type must be totp"),
+        }
+        let label = percent_decode_str(url.path().trim_start_matches('/'))
+            .decode_utf8_lossy()
+            .into_owned();
+
+        let mut secret = None;
+        let mut algorithm = Algorithm::Sha1;
+        let mut digits = 6;
+        let mut period = 30;
+        for (name, value) in url.query_pairs() {
+            match name.to_ascii_lowercase().as_str() {
+                "secret" => secret = Some(decode_base32(&value)?),
+                "algorithm" => {
+                    algorithm = match value.to_ascii_uppercase().as_str() {
+                        "SHA1" => Algorithm::Sha1,
+                        "SHA256" => Algorithm::Sha256,
+                        "SHA512" => Algorithm::Sha512,
+                        other => bail!("unsupported otpauth algorithm: {}", other),
+                    }
+                }
+                "digits" => {
+                    digits = match value.parse() {
+                        Ok(d @ 6..=8) => d,
+                        _ => bail!("otpauth digits must be 6, 7 or 8, got '{}'", value),
+                    }
+                }
+                "period" => {
+                    period = match value.parse() {
+                        Ok(p) if p > 0 => p,
+                        _ => bail!("otpauth period must be a positive number of seconds"),
+                    }
+                }
+                // issuer, image, ... are informational
+                _ => {}
+            }
+        }
+        let Some(secret) = secret.filter(|s| !s.is_empty()) else {
+            bail!("otpauth URI has no secret parameter");
+        };
+        Ok(Self {
+            secret,
+            algorithm,
+            digits,
+            period,
+            label,
+        })
+    }
+
+    /// Code for the time step containing `unix_secs`.
+    pub fn code_at(&self, unix_secs: u64) -> String {
+        let counter = (unix_secs / self.period).to_be_bytes();
+        let mac = match self.algorithm {
+            Algorithm::Sha1 => hmac::<Hmac<Sha1>>(&self.secret, &counter),
+            Algorithm::Sha256 => hmac::<Hmac<Sha256>>(&self.secret, &counter),
+            Algorithm::Sha512 => hmac::<Hmac<Sha512>>(&self.secret, &counter),
+        };
+        // Dynamic truncation, RFC 4226 section 5.3
+        let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+        let bin = u32::from_be_bytes([
+            mac[offset] & 0x7f,
+            mac[offset + 1],
+            mac[offset + 2],
+            mac[offset + 3],
+        ]);
+        format!(
+            "{:0width$}",
+            bin % 10u32.pow(self.digits),
+            width = self.digits as usize
+        )
+    }
+
+    /// Seconds until the code for `unix_secs` expires.
+    pub fn seconds_remaining(&self, unix_secs: u64) -> u64 {
+        self.period - unix_secs % self.period
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// Current code and the seconds it stays valid.
+    pub fn now(&self) -> (String, u64) {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        (self.code_at(secs), self.seconds_remaining(secs))
+    }
+}
+
+fn hmac<M: Mac + hmac::digest::KeyInit>(key: &[u8], msg: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(msg);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// RFC 4648 base32, case-insensitive, ignoring spaces and `=` padding.
+fn decode_base32(input: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let mut out = Zeroizing::new(Vec::with_capacity(input.len() * 5 / 8));
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u32 - 'A' as u32,
+            c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+            _ => bail!("otpauth secret is not valid base32"),
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
+
+/// Print the current code of an `otpauth://` credential.
+pub fn cmd_otp(args: ProjectKeyArgs) -> Result<()> {
+    let dir = envops::resolve_set_dir(&args.project)?;
+    let map = envops::read_env_map(&dir)?;
+    let Some(value) = map.get(&args.key) else {
+        bail!("key not found");
+    };
+    if !is_otpauth(value) {
+        bail!(
+            "'{}' is not a one-time password credential (expected an otpauth:// URI)",
+            args.key
+        );
+    }
+    let totp = Totp::from_uri(value)?;
+    let (code, remaining) = totp.now();
+    println!("{}", code);
+    // Keep `CODE=$(safehold otp ...)` to a single line
+    if !std::io::stdout().is_terminal() {
+        return Ok(());
+    }
+    if totp.label.is_empty() {
+        styles::info(format!("⏱️ Valid for {}s more", remaining));
+    } else {
+        styles::info(format!("⏱️ {}: valid for {}s more", totp.label, remaining));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rfc_totp(algorithm: Algorithm, seed: &[u8]) -> Totp {
+        Totp {
+            secret: Zeroizing::new(seed.to_vec()),
+            algorithm,
+            digits: 8,
+            period: 30,
+            label: String::new(),
+        }
+    }
+
+    #[test]
+    fn matches_rfc6238_test_vectors() {
+        let sha1 = rfc_totp(Algorithm::Sha1, b"12345678901234567890");
+        let sha256 = rfc_totp(Algorithm::Sha256, b"12345678901234567890123456789012");
+        let sha512 = rfc_totp(
+            Algorithm::Sha512,
+            b"1234567890123456789012345678901234567890123456789012345678901234",
+        );
+        assert_eq!(sha1.code_at(59), "94287082");
+        assert_eq!(sha256.code_at(59), "46119246");
+        assert_eq!(sha512.code_at(59), "90693936");
+        assert_eq!(sha1.code_at(1111111109), "07081804");
+        assert_eq!(sha256.code_at(1234567890), "91819424");
+        assert_eq!(sha512.code_at(20000000000), "47863826");
+        assert_eq!(sha1.seconds_remaining(59), 1);
+    }
+
+    #[test]
+    fn parses_otpauth_uris() {
+        let totp = Totp::from_uri(
+            "otpauth://totp/ACME%20Co:alice@example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=ACME%20Co&digits=8", // safehold:allow
+        )
+        .unwrap();
+        assert_eq!(totp.label, "ACME Co:alice@example.com");
+        assert_eq!(totp.code_at(59), "94287082");
+        assert!(is_otpauth("OTPAUTH://totp/x?secret=AA"));
+
+        assert!(validate_value("not a uri").is_ok());
+        assert!(validate_value("otpauth://totp/x").is_err());
+        assert!(validate_value("otpauth://totp/x?secret=not-base32!").is_err());
+        assert!(validate_value("otpauth://hotp/x?secret=GEZDGNBV&counter=1").is_err());
+        assert!(validate_value("otpauth://totp/x?secret=GEZDGNBV&digits=4").is_err());
+    }
+}
@@ -0,0 +1,291 @@
+//! Where project and master passwords come from when nobody is typing.
+//!
+//! Sources are tried in order, and the first that applies wins:
+//! 1. `--password-file [PROJECT=]PATH` and `--password-fd [PROJECT=]FD`; a
+//!    `PROJECT=` prefix (ID, name, `global` or `master`) binds the source to
+//!    one project, so `run --with-global` can unlock both with different
+//!    passwords. Sources without a prefix apply to any project.
+//! 2. `SAFEHOLD_PASSWORD` for projects, `SAFEHOLD_MASTER_PASSWORD` for the
+//!    Global Master Lock.
+//! 3. `SAFEHOLD_PASSWORD_COMMAND`, run through the shell with `SAFEHOLD_PROJECT`
+//!    and `SAFEHOLD_PROJECT_ID` set (`master` for the master password), e.g.
+//!    `pass show safehold/$SAFEHOLD_PROJECT`.
+//!
+//! Only the first line of a file, descriptor or command output is used. Each
+//! file or descriptor is read once per run, and the command runs once per
+//! project (so a Global Master Lock asks it only once).
+
+use crate::core::secret::SecretString;
+use crate::operations::process;
+use anyhow::{Context, Result, bail};
+use once_cell::sync::{Lazy, OnceCell};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Mutex;
+use zeroize::Zeroizing;
+
+/// Environment variable naming a password helper command.
+pub const PASSWORD_COMMAND_VAR: &str = "SAFEHOLD_PASSWORD_COMMAND";
+
+/// A `--password-file` or `--password-fd` argument.
+#[derive(Debug, Clone)]
+pub struct PasswordSource {
+    /// Project ID or name (or `global`/`master`) this source is limited to.
+    project: Option<String>,
+    origin: Origin,
+}
+
+#[derive(Debug, Clone)]
+enum Origin {
+    File(PathBuf),
+    Fd(i32),
+}
+
+/// Split an optional `PROJECT=` prefix off a source argument.
+fn split_project(value: &str) -> (Option<String>, &str) {
+    match value.split_once('=') {
+        Some((project, rest)) if !project.is_empty() && !project.contains(['/', '\\']) => {
+            (Some(project.to_string()), rest)
+        }
+        _ => (None, value),
+    }
+}
+
+/// Parse `--password-file [PROJECT=]PATH`.
+pub fn parse_file_source(value: &str) -> Result<PasswordSource, String> {
+    let (project, path) = split_project(value);
+    if path.is_empty() {
+        return Err("expected [PROJECT=]PATH".to_string());
+    }
+    Ok(PasswordSource {
+        project,
+        origin: Origin::File(PathBuf::from(path)),
+    })
+}
+
+/// Parse `--password-fd [PROJECT=]FD`.
+pub fn parse_fd_source(value: &str) -> Result<PasswordSource, String> {
+    let (project, fd) = split_project(value);
+    let fd = fd
+        .parse::<i32>()
+        .ok()
+        .filter(|fd| *fd >= 0)
+        .ok_or_else(|| format!("'{}' is not a file descriptor number", fd))?;
+    Ok(PasswordSource {
+        project,
+        origin: Origin::Fd(fd),
+    })
+}
+
+static SOURCES: OnceCell<Vec<PasswordSource>> = OnceCell::new();
+/// Passwords already read, by index into `SOURCES`.
+static READ: Lazy<Mutex<HashMap<usize, SecretString>>> = Lazy::new(Default::default);
+/// Passwords printed by `SAFEHOLD_PASSWORD_COMMAND`, by target ID.
+static COMMAND_READ: Lazy<Mutex<HashMap<String, SecretString>>> = Lazy::new(Default::default);
+
+/// Register the sources given on the command line once at startup.
+///
+/// Descriptors are marked close-on-exec so that a source that is never read
+/// (an unlocked project, or one bound to another project) does not leak into
+/// `run` children.
+pub fn init(sources: Vec<PasswordSource>) {
+    #[cfg(unix)]
+    for source in &sources {
+        if let Origin::Fd(fd) = source.origin
+            && fd > 2
+        {
+            // SAFETY: fcntl on a descriptor number has no memory effects and
+            // simply fails if it is not open
+            unsafe {
+                let flags = libc::fcntl(fd, libc::F_GETFD);
+                if flags >= 0 {
+                    libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC);
+                }
+            }
+        }
+    }
+    let _ = SOURCES.set(sources);
+}
+
+/// What a password unlocks.
+#[derive(Debug, Clone, Copy)]
+pub enum Target<'a> {
+    Master,
+    Project { id: &'a str, name: &'a str },
+}
+
+impl Target<'_> {
+    fn id(&self) -> &str {
+        match self {
+            Target::Master => "master",
+            Target::Project { id, .. } => id,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Target::Master => "master",
+            Target::Project { name, .. } => name,
+        }
+    }
+
+    fn env_var(&self) -> &'static str {
+        match self {
+            Target::Master => "SAFEHOLD_MASTER_PASSWORD",
+            Target::Project { .. } => "SAFEHOLD_PASSWORD",
+        }
+    }
+
+    fn prompt(&self) -> String {
+        match self {
+            Target::Master => "Master Password: ".to_string(),
+            Target::Project { name, .. } => format!("Password for '{}': ", name),
+        }
+    }
+}
+
+/// Index of the command-line source for `target`: its own first, then a shared one.
+fn source_for(target: Target) -> Option<usize> {
+    let sources = SOURCES.get()?;
+    let matches = |p: &str| p == target.id() || p == target.name();
+    sources
+        .iter()
+        .position(|s| s.project.as_deref().is_some_and(matches))
+        .or_else(|| sources.iter().position(|s| s.project.is_none()))
+}
+
+/// Whether a password for `target` is available without prompting.
+pub fn available(target: Target) -> bool {
+    source_for(target).is_some()
+        || std::env::var_os(target.env_var()).is_some()
+        || std::env::var_os(PASSWORD_COMMAND_VAR).is_some_and(|c| !c.is_empty())
+}
+
+/// The password for `target`, prompting only when no other source provides it.
+pub fn password(target: Target) -> Result<SecretString> {
+    if let Some(index) = source_for(target) {
+        return read_source(index);
+    }
+    if let Ok(password) = std::env::var(target.env_var()) {
+        return Ok(password.into());
+    }
+    if let Some(command) = std::env::var(PASSWORD_COMMAND_VAR)
+        .ok()
+        .filter(|c| !c.is_empty())
+    {
+        return run_command(&command, target);
+    }
+    Ok(rpassword::prompt_password(target.prompt())?.into())
+}
+
+fn read_source(index: usize) -> Result<SecretString> {
+    let mut cache = READ.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(password) = cache.get(&index) {
+        return Ok(password.clone());
+    }
+    let source = &SOURCES.get().expect("password sources initialized")[index];
+    let contents = match &source.origin {
+        Origin::File(path) => SecretString::from(
+            std::fs::read_to_string(path)
+                .with_context(|| format!("read password file {}", path.display()))?,
+        ),
+        Origin::Fd(fd) => read_fd(*fd)?,
+    };
+    let password = first_line(&contents);
+    cache.insert(index, password.clone());
+    Ok(password)
+}
+
+#[cfg(unix)]
+fn read_fd(fd: i32) -> Result<SecretString> {
+    // SAFETY: F_GETFD only inspects the descriptor table
+    if unsafe { libc::fcntl(fd, libc::F_GETFD) } < 0 {
+        bail!("file descriptor {} is not open", fd);
+    }
+    let mut contents = Zeroizing::new(Vec::new());
+    let mut buf = Zeroizing::new([0u8; 4096]);
+    let read = loop {
+        // SAFETY: `buf` is valid for `buf.len()` bytes and the descriptor is
+        // only borrowed; it is closed below, after reading
+        let n = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        match n {
+            0 => break Ok(()),
+            n if n > 0 => contents.extend_from_slice(&buf[..n as usize]),
+            _ => {
+                let err = std::io::Error::last_os_error();
+                if err.kind() != std::io::ErrorKind::Interrupted {
+                    break Err(err);
+                }
+            }
+        }
+    };
+    // Stdio is left open; other descriptors were handed over for this read only
+    if fd > 2 {
+        // SAFETY: nothing else in SafeHold uses this descriptor
+        unsafe { libc::close(fd) };
+    }
+    read.with_context(|| format!("read password from file descriptor {}", fd))?;
+    let text = std::str::from_utf8(&contents)
+        .with_context(|| format!("password from file descriptor {} is not UTF-8", fd))?;
+    Ok(SecretString::from(text))
+}
+
+#[cfg(not(unix))]
+fn read_fd(_fd: i32) -> Result<SecretString> {
+    bail!("--password-fd is only supported on Unix platforms")
+}
+
+fn run_command(command: &str, target: Target) -> Result<SecretString> {
+    let mut cache = COMMAND_READ.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(password) = cache.get(target.id()) {
+        return Ok(password.clone());
+    }
+    let mut cmd = process::shell(command);
+    // The helper sees neither our password variables nor other SafeHold settings
+    process::EnvFilter::default().apply(&mut cmd);
+    let output = cmd
+        .env("SAFEHOLD_PROJECT", target.name())
+        .env("SAFEHOLD_PROJECT_ID", target.id())
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("run {}", PASSWORD_COMMAND_VAR))?;
+    let raw = Zeroizing::new(output.stdout);
+    let stdout = SecretString::from(String::from_utf8_lossy(&raw).into_owned());
+    if !output.status.success() {
+        bail!(
+            "{} failed for '{}' ({})",
+            PASSWORD_COMMAND_VAR,
+            target.name(),
+            output.status
+        );
+    }
+    let password = first_line(&stdout);
+    cache.insert(target.id().to_string(), password.clone());
+    Ok(password)
+}
+
+/// First line without its line ending, the way `pass` and friends print secrets.
+fn first_line(text: &SecretString) -> SecretString {
+    let line = text.expose().lines().next().unwrap_or_default();
+    SecretString::from(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_project_prefixes() {
+        let shared = parse_file_source("/run/secrets/pw").unwrap();
+        assert!(shared.project.is_none());
+        let scoped = parse_fd_source("global=3").unwrap();
+        assert_eq!(scoped.project.as_deref(), Some("global"));
+        assert!(matches!(scoped.origin, Origin::Fd(3)));
+        // An `=` inside a path is not a project prefix
+        let odd = parse_file_source("./dir/a=b").unwrap();
+        assert!(odd.project.is_none());
+        assert!(parse_fd_source("api=x").is_err());
+    }
+}
@@ -0,0 +1,382 @@
+//! Child process supervision for `run`
+//!
+//! Spawns the target command, forwards termination signals to it while SafeHold
+//! waits, and carries the child's exact exit status back to `main` so that
+//! `safehold run` is transparent to shells and CI runners. Decides which of
+//! SafeHold's own environment the child inherits.
+
+use crate::core::hardening;
+use crate::operations::redact::Redactor;
+use anyhow::{Context, Result};
+use forwarding::SignalForwarding;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::thread;
+
+/// A command line run through the platform shell (`sh -c`, or `cmd /C` on Windows).
+pub fn shell(cmdline: &str) -> Command {
+    #[cfg(windows)]
+    let mut cmd = Command::new("cmd");
+    #[cfg(windows)]
+    cmd.arg("/C");
+    #[cfg(not(windows))]
+    let mut cmd = Command::new("sh");
+    #[cfg(not(windows))]
+    cmd.arg("-c");
+    cmd.arg(cmdline);
+    cmd
+}
+
+/// Prefix of SafeHold's control variables (passwords, helper command, home).
+pub const CONTROL_PREFIX: &str = "SAFEHOLD_";
+
+/// Whether `name` is one of SafeHold's control variables (case-insensitive,
+/// as Windows treats variable names).
+fn is_control(name: &str) -> bool {
+    name.get(..CONTROL_PREFIX.len())
+        .is_some_and(|p| p.eq_ignore_ascii_case(CONTROL_PREFIX))
+}
+
+/// Match an environment variable name against a pattern where `*` stands
+/// for any run of characters.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let Some((first, rest)) = pattern.split_once('*') else {
+        return pattern == name;
+    };
+    let Some(mut tail) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let last = parts.pop().unwrap_or_default();
+    for part in parts {
+        match tail.find(part) {
+            Some(at) => tail = &tail[at + part.len()..],
+            None => return false,
+        }
+    }
+    tail.len() >= last.len() && tail.ends_with(last)
+}
+
+/// Which of SafeHold's own environment variables a child inherits.
+///
+/// `SAFEHOLD_*` control variables are always withheld unless an allow pattern
+/// that itself starts with `SAFEHOLD_` names them (`*` alone never does).
+/// Other allow patterns turn inheritance into an allowlist; deny patterns
+/// remove variables either way. Injected credentials are set afterwards and
+/// are not affected.
+#[derive(Debug, Clone, Default)]
+pub struct EnvFilter {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl EnvFilter {
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        EnvFilter {
+            allow: allow.to_vec(),
+            deny: deny.to_vec(),
+        }
+    }
+
+    /// Whether the parent's variable `name` is passed to the child.
+    pub fn inherits(&self, name: &str) -> bool {
+        let matches = |pattern: &String| wildcard_match(pattern, name);
+        if self.deny.iter().any(matches) {
+            return false;
+        }
+        let mut allow = self
+            .allow
+            .iter()
+            .filter(|p| is_control(p) == is_control(name))
+            .peekable();
+        if is_control(name) {
+            allow.any(matches)
+        } else {
+            allow.peek().is_none() || allow.any(matches)
+        }
+    }
+
+    /// Remove the variables the child must not inherit from `cmd`; set
+    /// injected values after calling this.
+    pub fn apply(&self, cmd: &mut Command) {
+        let restricted = self.allow.iter().any(|p| !is_control(p));
+        for (name, _) in std::env::vars_os() {
+            let keep = match name.to_str() {
+                Some(name) => self.inherits(name),
+                // Names that are not Unicode cannot match an allow pattern
+                None => !restricted,
+            };
+            if !keep {
+                cmd.env_remove(&name);
+            }
+        }
+    }
+}
+
+/// Non-successful exit of a child started by `run`.
+///
+/// Returned as an error so callers (CLI or GUI) can report it; `main` downcasts
+/// it and terminates with the very same status instead of a generic `1`.
+#[derive(Debug, thiserror::Error)]
+pub enum ChildExit {
+    #[error("process exited with status {0}")]
+    Code(i32),
+    #[error("process terminated by signal {0}")]
+    Signal(i32),
+}
+
+impl ChildExit {
+    /// Classify a finished child's status; `None` when it succeeded.
+    pub fn from_status(status: ExitStatus) -> Option<Self> {
+        if status.success() {
+            return None;
+        }
+        if let Some(code) = status.code() {
+            return Some(ChildExit::Code(code));
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            if let Some(sig) = status.signal() {
+                return Some(ChildExit::Signal(sig));
+            }
+        }
+        Some(ChildExit::Code(1))
+    }
+
+    /// Terminate the current process mirroring the child's status.
+    ///
+    /// For death by signal the same signal is re-raised with its default
+    /// disposition (core dumps disabled first), falling back to `128 + signal`.
+    pub fn exit(&self) -> ! {
+        match *self {
+            ChildExit::Code(code) => std::process::exit(code),
+            ChildExit::Signal(sig) => {
+                #[cfg(unix)]
+                unsafe {
+                    let no_core = libc::rlimit {
+                        rlim_cur: 0,
+                        rlim_max: 0,
+                    };
+                    libc::setrlimit(libc::RLIMIT_CORE, &no_core);
+                    libc::signal(sig, libc::SIG_DFL);
+                    libc::raise(sig);
+                }
+                std::process::exit(128 + sig)
+            }
+        }
+    }
+}
+
+/// Spawn `cmd`, forward termination signals to it and wait for it to exit.
+///
+/// With a `redactor`, the child's stdout and stderr are piped through it instead
+/// of being inherited.
+pub fn spawn_and_wait(cmd: &mut Command, redactor: Option<Arc<Redactor>>) -> Result<ExitStatus> {
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    if redactor.is_some() {
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+    hardening::restore_for_child(cmd);
+    let _forwarding = SignalForwarding::install();
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("failed to start '{}'", program))?;
+    SignalForwarding::attach(child.id());
+
+    let mut pumps = Vec::new();
+    if let Some(redactor) = redactor {
+        if let Some(out) = child.stdout.take() {
+            let r = Arc::clone(&redactor);
+            pumps.push(thread::spawn(move || r.pump(out, std::io::stdout())));
+        }
+        if let Some(err) = child.stderr.take() {
+            let r = Arc::clone(&redactor);
+            pumps.push(thread::spawn(move || r.pump(err, std::io::stderr())));
+        }
+    }
+
+    let status = child
+        .wait()
+        .with_context(|| format!("failed to wait for '{}'", program))?;
+    for pump in pumps {
+        // A closed stdout on our side (e.g. `| head`) is not the child's failure
+        let _ = pump.join();
+    }
+    Ok(status)
+}
+
+/// Replace the current process image with `cmd` (Unix only).
+///
+/// Only returns if the exec itself failed.
+#[cfg(unix)]
+pub fn exec(cmd: &mut Command) -> Result<()> {
+    use std::io::Write;
+    use std::os::unix::process::CommandExt;
+
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    std::io::stdout().flush().ok();
+    std::io::stderr().flush().ok();
+    hardening::restore_for_child(cmd);
+    let err = cmd.exec();
+    Err(err).with_context(|| format!("failed to exec '{}'", program))
+}
+
+/// Replace the current process image with `cmd` (Unix only).
+#[cfg(not(unix))]
+pub fn exec(_cmd: &mut Command) -> Result<()> {
+    anyhow::bail!("--exec is only supported on Unix platforms")
+}
+
+#[cfg(unix)]
+mod forwarding {
+    use std::sync::atomic::{AtomicI32, AtomicU32, Ordering};
+
+    /// Signals relayed to the child while SafeHold waits for it.
+    const FORWARDED: [libc::c_int; 6] = [
+        libc::SIGINT,
+        libc::SIGTERM,
+        libc::SIGHUP,
+        libc::SIGQUIT,
+        libc::SIGUSR1,
+        libc::SIGUSR2,
+    ];
+
+    /// PID of the running child, 0 while none is attached.
+    static CHILD_PID: AtomicI32 = AtomicI32::new(0);
+    /// Signals received before the child was attached, one bit per `FORWARDED` entry.
+    static PENDING: AtomicU32 = AtomicU32::new(0);
+
+    fn bit(sig: libc::c_int) -> u32 {
+        FORWARDED
+            .iter()
+            .position(|s| *s == sig)
+            .map_or(0, |i| 1 << i)
+    }
+
+    /// Relay a signal sent by another process (`kill`, a supervisor, CI).
+    ///
+    /// Signals generated by the terminal (Ctrl+C, Ctrl+\, hangup) carry no
+    /// sender PID; they already reach the child, which shares our foreground
+    /// process group, so relaying them would deliver them twice.
+    extern "C" fn relay(sig: libc::c_int, info: *mut libc::siginfo_t, _: *mut libc::c_void) {
+        // SAFETY: the kernel passes a valid siginfo_t to SA_SIGINFO handlers
+        let sender = if info.is_null() {
+            0
+        } else {
+            unsafe { (*info).si_pid() }
+        };
+        let pid = CHILD_PID.load(Ordering::SeqCst);
+        if pid == 0 {
+            // Not attached yet: the child may not exist, so deliver it once it does
+            PENDING.fetch_or(bit(sig), Ordering::SeqCst);
+        } else if sender != 0 {
+            // kill(2) is async-signal-safe
+            unsafe {
+                libc::kill(pid, sig);
+            }
+        }
+    }
+
+    /// Installed relay handlers; previous dispositions are restored on drop.
+    pub struct SignalForwarding {
+        previous: Vec<(libc::c_int, libc::sigaction)>,
+    }
+
+    impl SignalForwarding {
+        pub fn install() -> Self {
+            PENDING.store(0, Ordering::SeqCst);
+            let mut previous = Vec::with_capacity(FORWARDED.len());
+            for sig in FORWARDED {
+                unsafe {
+                    let mut action: libc::sigaction = std::mem::zeroed();
+                    action.sa_sigaction = relay as *const () as libc::sighandler_t;
+                    action.sa_flags = libc::SA_RESTART | libc::SA_SIGINFO;
+                    libc::sigemptyset(&mut action.sa_mask);
+                    let mut old: libc::sigaction = std::mem::zeroed();
+                    if libc::sigaction(sig, &action, &mut old) == 0 {
+                        previous.push((sig, old));
+                    }
+                }
+            }
+            SignalForwarding { previous }
+        }
+
+        /// Start relaying to `pid`, delivering anything that arrived before it existed.
+        pub fn attach(pid: u32) {
+            CHILD_PID.store(pid as i32, Ordering::SeqCst);
+            let pending = PENDING.swap(0, Ordering::SeqCst);
+            for (i, sig) in FORWARDED.iter().enumerate() {
+                if pending & (1 << i) != 0 {
+                    unsafe {
+                        libc::kill(pid as i32, *sig);
+                    }
+                }
+            }
+        }
+    }
+
+    impl Drop for SignalForwarding {
+        fn drop(&mut self) {
+            CHILD_PID.store(0, Ordering::SeqCst);
+            for (sig, old) in &self.previous {
+                unsafe {
+                    libc::sigaction(*sig, old, std::ptr::null_mut());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod forwarding {
+    /// On Windows the child shares our console and receives Ctrl+C itself;
+    /// SafeHold only has to survive the event so it can report the exit code.
+    pub struct SignalForwarding;
+
+    impl SignalForwarding {
+        pub fn install() -> Self {
+            ctrlc::set_handler(|| {}).ok();
+            SignalForwarding
+        }
+
+        pub fn attach(_pid: u32) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards_match_whole_names() {
+        assert!(wildcard_match("AWS_*", "AWS_REGION"));
+        assert!(wildcard_match("*_TOKEN", "GITHUB_TOKEN"));
+        assert!(wildcard_match("A*B*C", "AxxBxxC"));
+        assert!(wildcard_match("*", "ANYTHING"));
+        assert!(!wildcard_match("AWS_*", "MY_AWS_REGION"));
+        assert!(!wildcard_match("A*BC", "ABC_"));
+        assert!(!wildcard_match("PATH", "PATHEXT"));
+    }
+
+    #[test]
+    fn control_variables_need_an_explicit_allow() {
+        let default = EnvFilter::default();
+        assert!(default.inherits("PATH"));
+        assert!(!default.inherits("SAFEHOLD_PASSWORD"));
+        assert!(!default.inherits("safehold_home"));
+
+        let all = EnvFilter::new(&["*".into()], &[]);
+        assert!(all.inherits("HOME"));
+        assert!(!all.inherits("SAFEHOLD_MASTER_PASSWORD"));
+
+        // A SafeHold pattern alone does not restrict anything else
+        let home = EnvFilter::new(&["SAFEHOLD_HOME".into()], &[]);
+        assert!(home.inherits("SAFEHOLD_HOME") && home.inherits("PATH"));
+        assert!(!home.inherits("SAFEHOLD_PASSWORD"));
+
+        let strict = EnvFilter::new(&["PATH".into(), "LC_*".into()], &["LC_ALL".into()]);
+        assert!(strict.inherits("PATH") && strict.inherits("LC_CTYPE"));
+        assert!(!strict.inherits("HOME") && !strict.inherits("LC_ALL"));
+    }
+}
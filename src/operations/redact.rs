@@ -0,0 +1,237 @@
+//! Streaming output redaction for `run --redact`
+//!
+//! Masks every injected credential value in a child's output, including its
+//! base64 and URL-encoded forms. Matching is done over a byte stream, so a value
+//! split across two reads is still caught: bytes that could be the start of a
+//! secret are held back until the next chunk decides.
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+/// Values shorter than this are not redacted; masking one- or two-character
+/// values would garble ordinary output without protecting anything.
+const MIN_REDACT_LEN: usize = 3;
+
+/// A single byte sequence to mask and its replacement text.
+struct Pattern {
+    needle: Vec<u8>,
+    replacement: Vec<u8>,
+}
+
+/// Set of secret forms to mask, shared by the stdout and stderr pumps.
+pub struct Redactor {
+    patterns: Vec<Pattern>,
+    max_len: usize,
+}
+
+impl Redactor {
+    /// Build a redactor for every value in `map`, labelled by its key.
+    pub fn from_map(map: &BTreeMap<String, String>) -> Self {
+        let mut patterns: Vec<Pattern> = Vec::new();
+        for (key, value) in map {
+            if value.len() < MIN_REDACT_LEN {
+                continue;
+            }
+            let replacement = format!("***{}***", key).into_bytes();
+            for form in encoded_forms(value) {
+                if !patterns.iter().any(|p| p.needle == form.as_bytes()) {
+                    patterns.push(Pattern {
+                        needle: form.into_bytes(),
+                        replacement: replacement.clone(),
+                    });
+                }
+            }
+        }
+        // Longest first so the most specific form wins at a given offset
+        patterns.sort_by_key(|p| std::cmp::Reverse(p.needle.len()));
+        let max_len = patterns.first().map(|p| p.needle.len()).unwrap_or(0);
+        Self { patterns, max_len }
+    }
+
+    /// Whether there is anything to redact at all.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Start a new stream with its own carry-over buffer.
+    pub fn stream(self: &Arc<Self>) -> RedactingStream {
+        RedactingStream {
+            redactor: Arc::clone(self),
+            pending: Vec::with_capacity(self.max_len),
+        }
+    }
+
+    /// Copy `reader` to `writer`, masking secrets, until EOF.
+    pub fn pump<R: Read, W: Write>(
+        self: &Arc<Self>,
+        mut reader: R,
+        mut writer: W,
+    ) -> std::io::Result<()> {
+        let mut stream = self.stream();
+        let mut buf = [0u8; 8192];
+        let mut out = Vec::with_capacity(buf.len());
+        loop {
+            let n = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            out.clear();
+            stream.feed(&buf[..n], &mut out);
+            writer.write_all(&out)?;
+            writer.flush()?;
+        }
+        out.clear();
+        stream.finish(&mut out);
+        writer.write_all(&out)?;
+        writer.flush()
+    }
+}
+
+/// Per-stream matching state.
+pub struct RedactingStream {
+    redactor: Arc<Redactor>,
+    pending: Vec<u8>,
+}
+
+impl RedactingStream {
+    /// Process a chunk, appending everything that is safe to emit to `out`.
+    pub fn feed(&mut self, chunk: &[u8], out: &mut Vec<u8>) {
+        self.pending.extend_from_slice(chunk);
+        let consumed = self.scan(out, false);
+        self.pending.drain(..consumed);
+    }
+
+    /// Flush whatever is still held back at end of stream.
+    pub fn finish(&mut self, out: &mut Vec<u8>) {
+        self.scan(out, true);
+        self.pending.clear();
+    }
+
+    /// Emit redacted output for `pending`; returns how many bytes were consumed.
+    fn scan(&self, out: &mut Vec<u8>, at_eof: bool) -> usize {
+        let buf = &self.pending;
+        let patterns = &self.redactor.patterns;
+        let mut i = 0;
+        while i < buf.len() {
+            let rest = &buf[i..];
+            // A longer secret may still complete with the next chunk
+            if !at_eof
+                && rest.len() < self.redactor.max_len
+                && patterns
+                    .iter()
+                    .any(|p| p.needle.len() > rest.len() && p.needle.starts_with(rest))
+            {
+                return i;
+            }
+            if let Some(p) = patterns.iter().find(|p| rest.starts_with(&p.needle)) {
+                out.extend_from_slice(&p.replacement);
+                i += p.needle.len();
+            } else {
+                out.push(buf[i]);
+                i += 1;
+            }
+        }
+        i
+    }
+}
+
+/// The raw value plus the encodings it is commonly logged in.
+fn encoded_forms(value: &str) -> Vec<String> {
+    let bytes = value.as_bytes();
+    let mut forms = vec![
+        value.to_string(),
+        STANDARD.encode(bytes),
+        STANDARD_NO_PAD.encode(bytes),
+        URL_SAFE.encode(bytes),
+        URL_SAFE_NO_PAD.encode(bytes),
+        percent_encode(value, false),
+        percent_encode(value, true),
+    ];
+    forms.sort();
+    forms.dedup();
+    forms
+}
+
+/// RFC 3986 unreserved characters are left alone; everything else is encoded.
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Percent-encoding of `value`, with upper- or lowercase hex digits.
+fn percent_encode(value: &str, lowercase: bool) -> String {
+    let encoded = utf8_percent_encode(value, URL_COMPONENT).to_string();
+    if !lowercase {
+        return encoded;
+    }
+    // Only the hex digits after '%' change case; the rest is unreserved text
+    let mut out = String::with_capacity(encoded.len());
+    let mut hex_left = 0;
+    for c in encoded.chars() {
+        if c == '%' {
+            hex_left = 2;
+            out.push(c);
+        } else if hex_left > 0 {
+            hex_left -= 1;
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor() -> Arc<Redactor> {
+        let mut map = BTreeMap::new();
+        map.insert("API_KEY".to_string(), "s3cr3t value".to_string());
+        map.insert("SHORT".to_string(), "ab".to_string());
+        Arc::new(Redactor::from_map(&map))
+    }
+
+    fn run_chunks(chunks: &[&[u8]]) -> String {
+        let r = redactor();
+        let mut stream = r.stream();
+        let mut out = Vec::new();
+        for chunk in chunks {
+            stream.feed(chunk, &mut out);
+        }
+        stream.finish(&mut out);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn masks_raw_and_encoded_forms() {
+        let line = format!(
+            "raw=s3cr3t value b64={} url=s3cr3t%20value short=ab\n",
+            STANDARD.encode("s3cr3t value")
+        );
+        assert_eq!(
+            run_chunks(&[line.as_bytes()]),
+            "raw=***API_KEY*** b64=***API_KEY*** url=***API_KEY*** short=ab\n"
+        );
+    }
+
+    #[test]
+    fn masks_values_split_across_chunks() {
+        assert_eq!(
+            run_chunks(&[b"token: s3c", b"r3t val", b"ue done"]),
+            "token: ***API_KEY*** done"
+        );
+    }
+
+    #[test]
+    fn flushes_partial_prefix_at_eof() {
+        assert_eq!(run_chunks(&[b"ends with s3cr"]), "ends with s3cr");
+    }
+}
@@ -0,0 +1,227 @@
+//! Credential rotation through external scripts and plugins
+//!
+//! `rotate` runs a shell command, or a `safehold-rotate-<name>` plugin on
+//! PATH, with the old value on stdin and stores whatever it prints as the new
+//! value. The previous value goes to an encrypted per-project history
+//! (`history.enc`). If a verification command then fails, the old value is
+//! restored and the rejected one is kept in history too, since the external
+//! system may already be using it.
+
+use crate::cli::cli::RotateArgs;
+use crate::cli::styles;
+use crate::core::config::history_path;
+use crate::core::crypto;
+use crate::core::secret::{SecretKey, SecretString};
+use crate::operations::{envops, expiry, process, typed};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use zeroize::Zeroizing;
+
+/// Previous values kept per key.
+const HISTORY_LIMIT: usize = 10;
+
+/// Plugins are executables named `safehold-rotate-<name>`.
+const PLUGIN_PREFIX: &str = "safehold-rotate-";
+
+/// A value that was replaced by `rotate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HistoryEntry {
+    value: SecretString,
+    /// RFC 3339 timestamp of when the value was replaced or rejected
+    at: String,
+    /// `replaced` (the old value) or `rejected` (a new value that failed verification)
+    reason: String,
+}
+
+type History = BTreeMap<String, Vec<HistoryEntry>>;
+
+fn read_history(dir: &Path, key: &SecretKey) -> Result<History> {
+    let path = history_path(dir);
+    if !path.exists() {
+        return Ok(History::new());
+    }
+    let enc = fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+    let pt = crypto::decrypt_with_key(key, &enc)?;
+    serde_json::from_slice(&pt).with_context(|| format!("parse {}", path.display()))
+}
+
+fn write_history(dir: &Path, key: &SecretKey, history: &History) -> Result<()> {
+    let path = history_path(dir);
+    let pt = Zeroizing::new(serde_json::to_vec(history)?);
+    let ct = crypto::encrypt_with_key(key, &pt)?;
+    fs::write(&path, ct).with_context(|| format!("write {}", path.display()))
+}
+
+fn push_history(history: &mut History, key: &str, value: &str, reason: &str) {
+    let entries = history.entry(key.to_string()).or_default();
+    entries.push(HistoryEntry {
+        value: value.into(),
+        at: OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .unwrap_or_default(),
+        reason: reason.to_string(),
+    });
+    if entries.len() > HISTORY_LIMIT {
+        entries.drain(..entries.len() - HISTORY_LIMIT);
+    }
+}
+
+/// Rotate a key, or list its history with `--history`.
+pub fn cmd_rotate(args: RotateArgs) -> Result<()> {
+    let dir = envops::resolve_set_dir(&args.project)?;
+    // One key (and one password prompt) for the map and its history
+    let key = envops::load_key_for_dir(&dir)?;
+    let mut map = envops::read_env_map_with_key(&dir, &key)?;
+    let Some(old) = map.get(&args.key).map(|v| SecretString::from(v.as_str())) else {
+        bail!("key '{}' not found in project '{}'", args.key, args.project);
+    };
+    let mut history = read_history(&dir, &key)?;
+    if args.history {
+        show_history(&args.key, history.get(&args.key), args.reveal);
+        return Ok(());
+    }
+
+    let mut meta = expiry::load_meta(&dir, &key)?;
+    let mut settings = meta.get(&args.key).cloned().unwrap_or_default();
+    if args.command.is_some() || args.plugin.is_some() {
+        settings.rotate_command = args.command.clone();
+        settings.rotate_plugin = args.plugin.clone();
+    }
+    if args.verify.is_some() {
+        settings.verify_command = args.verify.clone().filter(|v| !v.is_empty());
+    }
+    let mut rotator = match (&settings.rotate_command, &settings.rotate_plugin) {
+        (Some(cmdline), _) => process::shell(cmdline),
+        (None, Some(plugin)) => {
+            let exe = which::which(format!("{}{}", PLUGIN_PREFIX, plugin)).with_context(|| {
+                format!(
+                    "rotation plugin '{}{}' not found on PATH",
+                    PLUGIN_PREFIX, plugin
+                )
+            })?;
+            Command::new(exe)
+        }
+        (None, None) => bail!(
+            "no rotation command configured for '{}'; pass --command or --plugin (add --save to remember it)",
+            args.key
+        ),
+    };
+
+    // Scripts get the caller's environment minus SafeHold's own passwords and settings
+    process::EnvFilter::default().apply(&mut rotator);
+    let pb = styles::spinner(format!("Rotating {}...", args.key));
+    let new = run_rotator(&mut rotator, &args, old.expose());
+    styles::finish_spinner(pb, "Done");
+    let new = new?;
+    if new == old {
+        bail!("rotation command returned the current value; nothing changed");
+    }
+    typed::validate_value(new.expose())?;
+
+    // History first, so the old value is never lost between the two writes
+    push_history(&mut history, &args.key, old.expose(), "replaced");
+    write_history(&dir, &key, &history)?;
+    map.insert(args.key.clone(), new.expose().to_string());
+    envops::write_env_map_with_key(&dir, &key, &map)?;
+
+    if let Some(verify) = &settings.verify_command {
+        let mut cmd = process::shell(verify);
+        process::EnvFilter::default().apply(&mut cmd);
+        cmd.envs(&typed::flatten(&map));
+        let ok = cmd.status().map(|s| s.success()).unwrap_or(false);
+        if !ok {
+            map.insert(args.key.clone(), old.expose().to_string());
+            envops::write_env_map_with_key(&dir, &key, &map)?;
+            push_history(&mut history, &args.key, new.expose(), "rejected");
+            write_history(&dir, &key, &history)?;
+            bail!(
+                "verification failed; restored the previous value of '{}' (the rejected value is kept in `rotate --history`)",
+                args.key
+            );
+        }
+    }
+
+    if args.save {
+        if settings.is_empty() {
+            meta.remove(&args.key);
+        } else {
+            meta.insert(args.key.clone(), settings.clone());
+        }
+        expiry::save_meta(&dir, &key, &meta)?;
+    }
+    expiry::record_change(&dir, &key, &args.key)?;
+    styles::success(format!(
+        "🔁 Rotated '{}' in project '{}'{}",
+        args.key,
+        args.project,
+        if settings.verify_command.is_some() {
+            " (verified)"
+        } else {
+            ""
+        }
+    ));
+    if args.save {
+        styles::info(
+            "💾 Rotation settings saved; next time run `safehold rotate` with just -p and -k",
+        );
+    }
+    Ok(())
+}
+
+/// Run the rotation command with the old value on stdin; its stdout is the new value.
+fn run_rotator(cmd: &mut Command, args: &RotateArgs, old: &str) -> Result<SecretString> {
+    cmd.env("SAFEHOLD_ROTATE_PROJECT", &args.project)
+        .env("SAFEHOLD_ROTATE_KEY", &args.key)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit());
+    let mut child = cmd.spawn().context("start rotation command")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A script that ignores stdin may exit before reading it
+        let _ = stdin.write_all(old.as_bytes());
+    }
+    let out = child
+        .wait_with_output()
+        .context("wait for rotation command")?;
+    if !out.status.success() {
+        bail!(
+            "rotation command failed ({}); the value was not changed",
+            out.status
+        );
+    }
+    let raw = Zeroizing::new(out.stdout);
+    let new = std::str::from_utf8(&raw).context("rotation command printed non-UTF-8 output")?;
+    // Stored values are trimmed, so compare and store the trimmed form
+    let new = new.trim();
+    if new.is_empty() {
+        bail!("rotation command printed no value; the value was not changed");
+    }
+    if new.contains('\n') {
+        bail!("rotation command printed more than one line; expected just the new value");
+    }
+    Ok(SecretString::from(new))
+}
+
+fn show_history(key: &str, entries: Option<&Vec<HistoryEntry>>, reveal: bool) {
+    styles::header(format!("🔁 Rotation history: {}", key));
+    match entries {
+        Some(entries) if !entries.is_empty() => {
+            for e in entries.iter().rev() {
+                let value = if reveal {
+                    e.value.expose().to_string()
+                } else {
+                    format!("({} chars, --reveal to show)", e.value.expose().len())
+                };
+                styles::bullet(format!("{}  {}  {}", e.at, e.reason, value));
+            }
+        }
+        _ => styles::info("No previous values recorded"),
+    }
+}
@@ -0,0 +1,420 @@
+//! Validation schemas: required keys, value formats and defaults
+//!
+//! A schema lists the env vars an app expects. It is stored per project in
+//! `schema.json`, or shared through a `safehold.schema.json` file committed to
+//! the repository. `check` reports every violation. `run`/`export` fill in
+//! defaults and warn, or refuse to continue with `--strict`; they only pick up
+//! a shared file with `--strict` or `--schema`, so a stray one in the working
+//! directory cannot change what another project injects. Checks apply to
+//! the variables as injected, so typed credentials are seen as their
+//! flattened fields.
+
+use crate::cli::cli::{CheckArgs, SchemaArgs};
+use crate::cli::styles;
+use crate::core::config::{self, schema_path};
+use crate::core::secret::SecretMap;
+use crate::operations::{envops, typed};
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Schema file picked up from the working directory when no other is given.
+pub const SHARED_SCHEMA_FILE: &str = "safehold.schema.json";
+
+/// Expected variables, by name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    #[serde(default)]
+    pub keys: BTreeMap<String, KeyRule>,
+}
+
+/// Rules for one variable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeyRule {
+    /// Must be present (after defaults); listed keys are required unless set to false
+    #[serde(default = "default_required")]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<Format>,
+    /// Regular expression the whole value must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Value injected when the key is missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// Built-in value formats.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Url,
+    Int,
+    Port,
+    Base64,
+    Json,
+}
+
+impl Format {
+    fn matches(self, value: &str) -> bool {
+        use base64::engine::general_purpose::{
+            STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD,
+        };
+        match self {
+            Format::Url => url::Url::parse(value).is_ok(),
+            Format::Int => value.parse::<i64>().is_ok(),
+            Format::Port => value.parse::<u16>().is_ok_and(|p| p > 0),
+            Format::Base64 => [STANDARD, URL_SAFE, STANDARD_NO_PAD, URL_SAFE_NO_PAD]
+                .iter()
+                .any(|engine| engine.decode(value).is_ok()),
+            Format::Json => serde_json::from_str::<serde_json::Value>(value).is_ok(),
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Format::Url => "a URL",
+            Format::Int => "an integer",
+            Format::Port => "a port number (1-65535)",
+            Format::Base64 => "base64",
+            Format::Json => "valid JSON",
+        }
+    }
+}
+
+/// A key that does not satisfy its rule.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub key: String,
+    pub problem: String,
+}
+
+impl KeyRule {
+    fn regex(&self) -> Result<Option<Regex>> {
+        self.pattern
+            .as_deref()
+            .map(|p| {
+                Regex::new(&format!("^(?:{})$", p))
+                    .with_context(|| format!("invalid pattern '{}'", p))
+            })
+            .transpose()
+    }
+
+    fn problem(&self, value: &str) -> Option<String> {
+        if let Some(format) = self.format
+            && !format.matches(value)
+        {
+            return Some(format!("is not {}", format.describe()));
+        }
+        match self.regex() {
+            Ok(Some(re)) if !re.is_match(value) => Some(format!(
+                "does not match pattern {}",
+                self.pattern.as_deref().unwrap_or_default()
+            )),
+            _ => None,
+        }
+    }
+
+    /// One-line description for `.env.example` comments.
+    pub(crate) fn hint(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(format) = self.format {
+            parts.push(format.describe().to_string());
+        }
+        if let Some(pattern) = &self.pattern {
+            parts.push(format!("matching {}", pattern));
+        }
+        if !self.required {
+            parts.push("optional".to_string());
+        }
+        match (&self.description, parts.is_empty()) {
+            (Some(d), true) => Some(d.clone()),
+            (Some(d), false) => Some(format!("{} ({})", d, parts.join(", "))),
+            (None, false) => Some(parts.join(", ")),
+            (None, true) => None,
+        }
+    }
+}
+
+impl Schema {
+    /// Read a schema file, rejecting bad patterns and defaults that break their own rule.
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read(path).with_context(|| format!("read {}", path.display()))?;
+        let schema: Schema =
+            serde_json::from_slice(&data).with_context(|| format!("parse {}", path.display()))?;
+        for (key, rule) in &schema.keys {
+            rule.regex()
+                .with_context(|| format!("schema key {}", key))?;
+            if let Some(default) = &rule.default
+                && let Some(problem) = rule.problem(default)
+            {
+                bail!("schema key {}: default value {}", key, problem);
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Fill in defaults for missing keys; returns the keys that were filled.
+    pub fn apply_defaults(&self, map: &mut SecretMap) -> Vec<String> {
+        let mut filled = Vec::new();
+        for (key, rule) in &self.keys {
+            if let Some(default) = &rule.default
+                && !map.contains_key(key)
+            {
+                map.insert(key.clone(), default.clone());
+                filled.push(key.clone());
+            }
+        }
+        filled
+    }
+
+    /// Every rule the map breaks, in key order.
+    pub fn check(&self, map: &BTreeMap<String, String>) -> Vec<Violation> {
+        self.keys
+            .iter()
+            .filter_map(|(key, rule)| {
+                let problem = match map.get(key) {
+                    None if rule.required => Some("is missing".to_string()),
+                    None => None,
+                    Some(value) => rule.problem(value),
+                };
+                problem.map(|problem| Violation {
+                    key: key.clone(),
+                    problem,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The schema in effect for a project and where it came from.
+///
+/// An explicit `--schema` file wins, then the project's stored schema, then
+/// (with `shared`) a `safehold.schema.json` in the current directory.
+pub fn resolve(
+    dir: &Path,
+    explicit: Option<&Path>,
+    shared: bool,
+) -> Result<Option<(Schema, String)>> {
+    if let Some(path) = explicit {
+        return Ok(Some((Schema::load(path)?, path.display().to_string())));
+    }
+    let stored = schema_path(dir);
+    if stored.exists() {
+        return Ok(Some((Schema::load(&stored)?, "project schema".to_string())));
+    }
+    let shared_path = PathBuf::from(SHARED_SCHEMA_FILE);
+    if shared && shared_path.exists() {
+        return Ok(Some((
+            Schema::load(&shared_path)?,
+            SHARED_SCHEMA_FILE.to_string(),
+        )));
+    }
+    Ok(None)
+}
+
+/// Apply the schema to variables about to be injected by `run` or `export`.
+///
+/// Defaults are filled in and reported. Violations, and a schema that cannot
+/// be read, are warnings, or an error with `strict`.
+pub fn enforce(
+    dir: &Path,
+    explicit: Option<&Path>,
+    strict: bool,
+    map: &mut SecretMap,
+) -> Result<()> {
+    let resolved = match resolve(dir, explicit, strict) {
+        Err(e) if !strict => {
+            styles::warn(format!("🧾 Ignoring schema: {:#}", e));
+            return Ok(());
+        }
+        resolved => resolved?,
+    };
+    let Some((schema, source)) = resolved else {
+        if strict {
+            bail!(
+                "--strict needs a schema: pass --schema <file>, set one with `safehold schema --set`, or add {}",
+                SHARED_SCHEMA_FILE
+            );
+        }
+        return Ok(());
+    };
+    let filled = schema.apply_defaults(map);
+    if !filled.is_empty() {
+        styles::warn(format!(
+            "🧾 Using default(s) from {} for {}",
+            source,
+            filled.join(", ")
+        ));
+    }
+    let violations = schema.check(map);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    for v in &violations {
+        let line = format!("🧾 {} {} ({})", v.key, v.problem, source);
+        if strict {
+            styles::error(line);
+        } else {
+            styles::warn(line);
+        }
+    }
+    if strict {
+        bail!("{} schema violation(s); not continuing", violations.len());
+    }
+    Ok(())
+}
+
+/// Show, set or remove a project's stored schema.
+pub fn cmd_schema(args: SchemaArgs) -> Result<()> {
+    let dir = envops::resolve_set_dir(&args.project)?;
+    let path = schema_path(&dir);
+    if args.clear {
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+        }
+        styles::success(format!("🧾 Schema removed from project '{}'", args.project));
+        return Ok(());
+    }
+    if let Some(file) = &args.set {
+        let schema = Schema::load(file)?;
+        fs::write(&path, serde_json::to_vec_pretty(&schema)?)
+            .with_context(|| format!("write {}", path.display()))?;
+        styles::success(format!(
+            "🧾 Schema with {} key(s) stored for project '{}'",
+            schema.keys.len(),
+            args.project
+        ));
+    }
+
+    let Some((schema, source)) = resolve(&dir, None, true)? else {
+        styles::info(format!(
+            "No schema for project '{}'; set one with --set <file> or add {}",
+            args.project, SHARED_SCHEMA_FILE
+        ));
+        return Ok(());
+    };
+    styles::header(format!("🧾 Schema: {} ({})", args.project, source));
+    for (key, rule) in &schema.keys {
+        let mut parts = vec![
+            if rule.required {
+                "required"
+            } else {
+                "optional"
+            }
+            .to_string(),
+        ];
+        if let Some(format) = rule.format {
+            parts.push(format.describe().to_string());
+        }
+        if let Some(pattern) = &rule.pattern {
+            parts.push(format!("pattern {}", pattern));
+        }
+        if let Some(default) = &rule.default {
+            parts.push(format!("default {}", default));
+        }
+        styles::kv(key, parts.join(", "));
+    }
+    Ok(())
+}
+
+/// Check a project's variables against its schema; fails on any violation.
+pub fn cmd_check(args: CheckArgs) -> Result<()> {
+    let dir = envops::resolve_set_dir(&args.project)?;
+    let Some((schema, source)) = resolve(&dir, args.schema.as_deref(), true)? else {
+        bail!(
+            "no schema for project '{}': pass --schema <file>, set one with `safehold schema --set`, or add {}",
+            args.project,
+            SHARED_SCHEMA_FILE
+        );
+    };
+    let mut map = envops::read_env_map(&dir)?;
+    if args.with_global {
+        let gmap = envops::read_env_map(&config::global_dir()?)?;
+        map.extend(gmap.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    let mut map = typed::flatten(&map);
+    let defaults = schema.apply_defaults(&mut map);
+    let violations = schema.check(&map);
+
+    styles::header(format!("🧾 Schema check: {} ({})", args.project, source));
+    for key in &defaults {
+        styles::info(format!("{} not set; the default will be used", key));
+    }
+    let unknown: Vec<&String> = map
+        .keys()
+        .filter(|k| !schema.keys.contains_key(*k))
+        .collect();
+    if !unknown.is_empty() {
+        styles::info(format!(
+            "{} key(s) not in the schema: {}",
+            unknown.len(),
+            unknown
+                .iter()
+                .map(|k| k.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    if violations.is_empty() {
+        styles::success(format!("All {} schema key(s) satisfied", schema.keys.len()));
+        return Ok(());
+    }
+    for v in &violations {
+        styles::error(format!("  {} {}", v.key, v.problem));
+    }
+    bail!("{} schema violation(s)", violations.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_formats_patterns_and_defaults() {
+        let schema: Schema = serde_json::from_str(
+            r#"{"keys": {
+                "DATABASE_URL": {"format": "url"},
+                "PORT": {"format": "port", "default": "8080"},
+                "TOKEN": {"pattern": "tok_[a-z]+"},
+                "FLAGS": {"format": "json", "required": false},
+                "SEED": {"format": "base64", "required": false}
+            }}"#,
+        )
+        .unwrap();
+        let mut map = SecretMap::new();
+        map.insert("TOKEN".to_string(), "tok_abc1".to_string());
+        map.insert("FLAGS".to_string(), "{\"a\":1}".to_string());
+        map.insert("SEED".to_string(), "aGVsbG8".to_string());
+        assert_eq!(schema.apply_defaults(&mut map), vec!["PORT".to_string()]);
+
+        let problems: Vec<(String, String)> = schema
+            .check(&map)
+            .into_iter()
+            .map(|v| (v.key, v.problem))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                ("DATABASE_URL".to_string(), "is missing".to_string()),
+                (
+                    "TOKEN".to_string(),
+                    "does not match pattern tok_[a-z]+".to_string()
+                ),
+            ]
+        );
+        assert!(!Format::Port.matches("0") && !Format::Int.matches("1.5"));
+    }
+}
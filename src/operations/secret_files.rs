@@ -0,0 +1,198 @@
+//! Private on-disk copies of secrets
+//!
+//! Some tools only accept a path to a credential (service-account JSON, TLS
+//! keys). `SecretDir` materializes such values in an owner-only directory,
+//! preferring RAM-backed `/dev/shm` on Linux, and shreds everything when dropped
+//! so the files disappear on normal exit, on error and on panic alike.
+//! `SecretFile` does the same for a single file at a caller-chosen path, as
+//! used by `export --temp`.
+
+use anyhow::{Context, Result};
+use rand::{RngCore, rng};
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Owner-only temporary directory holding secret files; shredded on drop.
+pub struct SecretDir {
+    path: PathBuf,
+    files: Vec<PathBuf>,
+}
+
+impl SecretDir {
+    /// Create a fresh private directory named `<prefix>-<random>`.
+    pub fn create(prefix: &str) -> Result<Self> {
+        let mut suffix = [0u8; 8];
+        rng().fill_bytes(&mut suffix);
+        let name = format!(
+            "{}-{}",
+            prefix,
+            suffix
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        );
+        let path = secret_tmp_root().join(name);
+
+        let mut builder = fs::DirBuilder::new();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(0o700);
+        }
+        builder
+            .create(&path)
+            .with_context(|| format!("create {}", path.display()))?;
+
+        Ok(Self {
+            path,
+            files: Vec::new(),
+        })
+    }
+
+    /// Write `contents` to a new owner-only file and return its path.
+    ///
+    /// The file is named after `name` with unsafe characters replaced; names
+    /// that end up alike (`A/B`, `A_B`) get a numeric suffix.
+    pub fn write(&mut self, name: &str, contents: &[u8]) -> Result<PathBuf> {
+        let base: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        // Compared case-insensitively for case-insensitive temp filesystems
+        let taken = |candidate: &str| {
+            candidate.is_empty()
+                || candidate.bytes().all(|b| b == b'.')
+                || self.files.iter().any(|f| {
+                    f.file_name()
+                        .is_some_and(|n| n.to_string_lossy().eq_ignore_ascii_case(candidate))
+                })
+        };
+        let mut file_name = base.clone();
+        let mut n = 2;
+        while taken(&file_name) {
+            file_name = format!("{}-{}", base, n);
+            n += 1;
+        }
+        let path = self.path.join(file_name);
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&path)
+            .with_context(|| format!("create {}", path.display()))?;
+        self.files.push(path.clone());
+        file.write_all(contents)
+            .with_context(|| format!("write {}", path.display()))?;
+        file.sync_all().ok();
+        Ok(path)
+    }
+}
+
+impl Drop for SecretDir {
+    fn drop(&mut self) {
+        for file in &self.files {
+            let _ = shred_file(file);
+        }
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Owner-only secret file at a fixed path; shredded on drop or via `shred`.
+///
+/// `shred` takes `&self` so a TTL timer thread can remove the file early while
+/// the owner still holds the guard; whichever comes first does the work.
+pub struct SecretFile {
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl SecretFile {
+    /// Write `contents` to `path` with mode 0600, replacing it when `overwrite`.
+    pub fn create(path: &Path, contents: &[u8], overwrite: bool) -> Result<Self> {
+        if overwrite && path.exists() {
+            // Recreate rather than truncate so a lax existing mode is not inherited
+            fs::remove_file(path).with_context(|| format!("remove {}", path.display()))?;
+        }
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(path)
+            .with_context(|| format!("create {}", path.display()))?;
+        let guard = Self {
+            path: Mutex::new(Some(path.to_path_buf())),
+        };
+        file.write_all(contents)
+            .with_context(|| format!("write {}", path.display()))?;
+        file.sync_all().ok();
+        Ok(guard)
+    }
+
+    /// Shred the file now; returns its path if this call removed it.
+    pub fn shred(&self) -> Option<PathBuf> {
+        let path = self
+            .path
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()?;
+        shred_file(&path).ok().map(|_| path)
+    }
+}
+
+impl Drop for SecretFile {
+    fn drop(&mut self) {
+        self.shred();
+    }
+}
+
+/// Overwrite a file with zeros, flush it to disk and delete it.
+///
+/// Best effort on copy-on-write and journaling filesystems, where old blocks
+/// may survive; RAM-backed storage avoids the problem entirely.
+pub fn shred_file(path: &Path) -> Result<()> {
+    if let Ok(mut file) = OpenOptions::new().write(true).open(path) {
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let zeros = [0u8; 4096];
+        let mut remaining = len;
+        file.seek(SeekFrom::Start(0))?;
+        while remaining > 0 {
+            let n = remaining.min(zeros.len() as u64) as usize;
+            file.write_all(&zeros[..n])?;
+            remaining -= n as u64;
+        }
+        file.sync_all().ok();
+    }
+    fs::remove_file(path).with_context(|| format!("remove {}", path.display()))
+}
+
+/// Where secret files go: `/dev/shm` when available (Linux), else the OS temp dir.
+fn secret_tmp_root() -> PathBuf {
+    #[cfg(target_os = "linux")]
+    {
+        let shm = Path::new("/dev/shm");
+        if shm.is_dir()
+            && fs::metadata(shm)
+                .map(|m| !m.permissions().readonly())
+                .unwrap_or(false)
+        {
+            return shm.to_path_buf();
+        }
+    }
+    std::env::temp_dir()
+}
@@ -0,0 +1,214 @@
+//! Comparing and copying credentials between projects, or to the clipboard
+//!
+//! Both sides are opened with their own key, so either may be locked; each
+//! password prompt names the project it unlocks. Values are only printed by
+//! `diff --reveal`; `copy -p` puts one on the clipboard, cleared later.
+
+use crate::cli::cli::{CopyArgs, DiffArgs};
+use crate::cli::styles;
+use crate::operations::{envops, example, expiry};
+use crate::utils::clipboard;
+use anyhow::{Result, bail};
+use std::collections::BTreeMap;
+
+/// How a key differs between two projects.
+#[derive(Debug, PartialEq, Eq)]
+enum Change<'a> {
+    /// Only in the second project
+    Added(&'a str),
+    /// Only in the first project
+    Removed(&'a str),
+    /// In both, with different values
+    Changed(&'a str, &'a str),
+}
+
+fn compare<'a>(
+    a: &'a BTreeMap<String, String>,
+    b: &'a BTreeMap<String, String>,
+) -> BTreeMap<&'a str, Change<'a>> {
+    let mut changes = BTreeMap::new();
+    for (key, old) in a {
+        match b.get(key) {
+            None => {
+                changes.insert(key.as_str(), Change::Removed(old));
+            }
+            Some(new) if new != old => {
+                changes.insert(key.as_str(), Change::Changed(old, new));
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, new) in b {
+        if !a.contains_key(key) {
+            changes.insert(key.as_str(), Change::Added(new));
+        }
+    }
+    changes
+}
+
+/// Compare two projects, or a project with an example file; fails when they differ.
+pub fn cmd_diff(args: DiffArgs) -> Result<()> {
+    if let (Some(project), Some(against)) = (&args.project, &args.against) {
+        return example::diff_against(project, against);
+    }
+    let [first, second] = args.projects.as_slice() else {
+        bail!("pass two projects, or --project with --against <file>");
+    };
+    let (dir_a, dir_b) = (
+        envops::resolve_set_dir(first)?,
+        envops::resolve_set_dir(second)?,
+    );
+    let a = envops::read_env_map(&dir_a)?;
+    let b = if dir_a == dir_b {
+        a.clone()
+    } else {
+        envops::read_env_map(&dir_b)?
+    };
+
+    let changes = compare(&a, &b);
+    if changes.is_empty() {
+        styles::success(format!(
+            "Projects '{}' and '{}' have the same {} credential(s)",
+            first,
+            second,
+            a.len()
+        ));
+        return Ok(());
+    }
+    styles::header(format!("🔍 {} → {}", first, second));
+    for (key, change) in &changes {
+        let line = match (change, args.reveal) {
+            (Change::Added(v), true) => format!("+ {}={}", key, v),
+            (Change::Added(_), false) => format!("+ {}", key),
+            (Change::Removed(v), true) => format!("- {}={}", key, v),
+            (Change::Removed(_), false) => format!("- {}", key),
+            (Change::Changed(old, new), true) => format!("~ {}: {} → {}", key, old, new),
+            (Change::Changed(..), false) => format!("~ {} (value differs)", key),
+        };
+        styles::bullet(line);
+    }
+    if !args.reveal {
+        styles::info("Values are hidden; pass --reveal to show them");
+    }
+    let count = |f: fn(&Change) -> bool| changes.values().filter(|c| f(c)).count();
+    bail!(
+        "{} added, {} removed, {} changed",
+        count(|c| matches!(c, Change::Added(_))),
+        count(|c| matches!(c, Change::Removed(_))),
+        count(|c| matches!(c, Change::Changed(..)))
+    )
+}
+
+/// Copy one value to the clipboard, cleared after `clipboard_clear_seconds`.
+fn copy_to_clipboard(project: &str, keys: &[String]) -> Result<()> {
+    let [key] = keys else {
+        bail!("pass exactly one key with -k to copy to the clipboard");
+    };
+    let map = envops::read_env_map(&envops::resolve_set_dir(project)?)?;
+    let Some(value) = map.get(key) else {
+        bail!("key '{}' not found in project '{}'", key, project);
+    };
+    let delay = clipboard::copy_secret(value)?;
+    styles::success(format!(
+        "📋 Copied '{}' from '{}' to the clipboard{}",
+        key,
+        project,
+        clipboard::clear_note(delay)
+    ));
+    Ok(())
+}
+
+/// Copy credentials from one project into another, or one value to the clipboard.
+pub fn cmd_copy(args: CopyArgs) -> Result<()> {
+    if let Some(project) = &args.project {
+        return copy_to_clipboard(project, &args.keys);
+    }
+    let (Some(from), Some(to)) = (&args.from, &args.to) else {
+        bail!("pass --from and --to, or -p with -k to copy to the clipboard");
+    };
+    let from_dir = envops::resolve_set_dir(from)?;
+    let to_dir = envops::resolve_set_dir(to)?;
+    if from_dir == to_dir {
+        bail!("source and destination are the same project");
+    }
+    let source = envops::read_env_map(&from_dir)?;
+    let missing: Vec<&str> = args
+        .keys
+        .iter()
+        .filter(|k| !source.contains_key(*k))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "key(s) not found in project '{}': {}",
+            from,
+            missing.join(", ")
+        );
+    }
+    // One key (and one password prompt) for reading and writing the destination
+    let to_key = envops::load_key_for_dir(&to_dir)?;
+    let mut dest = envops::read_env_map_with_key(&to_dir, &to_key)?;
+
+    let mut copied = Vec::new();
+    let mut conflicts = Vec::new();
+    for (key, value) in &source {
+        if !args.keys.is_empty() && !args.keys.contains(key) {
+            continue;
+        }
+        match dest.get(key) {
+            Some(existing) if existing == value => {}
+            Some(_) if !args.overwrite => conflicts.push(key.as_str()),
+            _ => {
+                dest.insert(key.clone(), value.clone());
+                copied.push(key.as_str());
+            }
+        }
+    }
+    if !copied.is_empty() {
+        envops::write_env_map_with_key(&to_dir, &to_key, &dest)?;
+        for key in &copied {
+            expiry::record_change(&to_dir, &to_key, key)?;
+        }
+    }
+    styles::success(format!(
+        "📋 Copied {} credential(s) from '{}' to '{}'",
+        copied.len(),
+        from,
+        to
+    ));
+    if !conflicts.is_empty() {
+        styles::warn(format!(
+            "Skipped {} key(s) that already differ in '{}' (use --overwrite): {}",
+            conflicts.len(),
+            to,
+            conflicts.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_added_removed_and_changed() {
+        let map = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let a = map(&[("SAME", "1"), ("OLD", "x"), ("DIFF", "a")]);
+        let b = map(&[("SAME", "1"), ("NEW", "y"), ("DIFF", "b")]);
+        let changes: Vec<_> = compare(&a, &b).into_iter().collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("DIFF", Change::Changed("a", "b")),
+                ("NEW", Change::Added("y")),
+                ("OLD", Change::Removed("x")),
+            ]
+        );
+    }
+}
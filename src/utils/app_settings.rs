@@ -50,6 +50,10 @@ pub struct CliSettings {
     pub verbose_help: bool,
     /// Confirm destructive operations by default
     pub confirm_destructive: bool,
+    /// Show values in `list`/`show-all` without `--reveal` (CLI twin of
+    /// `gui.show_passwords_default`)
+    #[serde(default)]
+    pub reveal_values_default: bool,
 }
 
 /// Security-related settings
@@ -81,6 +85,7 @@ impl Default for AppSettings {
                 default_style: "fancy".to_string(),
                 verbose_help: false,
                 confirm_destructive: true,
+                reveal_values_default: false,
             },
             security: SecuritySettings {
                 global_master_lock: false,  // Default is unlocked
@@ -178,6 +183,13 @@ pub fn set_global_master_lock(enabled: bool) -> Result<()> {
     })
 }
 
+/// Whether the CLI shows credential values without `--reveal`
+pub fn reveal_values_default() -> bool {
+    load_settings()
+        .map(|s| s.cli.reveal_values_default)
+        .unwrap_or(false)
+}
+
 /// Get CLI defaults for color and style
 #[allow(dead_code)]
 pub fn get_cli_defaults() -> (String, String) {
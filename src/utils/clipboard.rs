@@ -0,0 +1,200 @@
+//! Clipboard access with automatic clearing
+//!
+//! Values copied by SafeHold are wiped after `security.clipboard_clear_seconds`
+//! (0 keeps them), but only while the clipboard still holds that value, so
+//! anything copied in the meantime survives. Copies are marked so clipboard
+//! managers leave them out of their history.
+//!
+//! The CLI hands the value to a detached `safehold clipboard-clear` helper over
+//! a pipe (never on its command line). On X11 and Wayland the clipboard is
+//! served by the process that set it, so the helper takes it over and keeps it
+//! available until it is cleared, or until something else is copied when
+//! clearing is off. Setting `SAFEHOLD_CLIPBOARD_FILE` replaces the system
+//! clipboard with a plain file, for tests and headless machines.
+
+use crate::core::secret::SecretString;
+use crate::utils::app_settings;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// X11 and Wayland drop the clipboard contents when the process that set them exits.
+const OWNER_MUST_STAY: bool = cfg!(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "android",
+        target_os = "emscripten"
+    ))
+));
+
+/// Where copied text goes.
+enum Backend {
+    System,
+    File(PathBuf),
+}
+
+fn backend() -> Backend {
+    match std::env::var_os("SAFEHOLD_CLIPBOARD_FILE") {
+        Some(path) if !path.is_empty() => Backend::File(PathBuf::from(path)),
+        _ => Backend::System,
+    }
+}
+
+fn system() -> Result<arboard::Clipboard> {
+    arboard::Clipboard::new().context("open the system clipboard")
+}
+
+/// Leave a copy out of clipboard managers' history.
+fn exclude_from_history(set: arboard::Set<'_>) -> arboard::Set<'_> {
+    #[cfg(target_os = "macos")]
+    use arboard::SetExtApple as _;
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+    ))]
+    use arboard::SetExtLinux as _;
+    #[cfg(windows)]
+    use arboard::SetExtWindows as _;
+    set.exclude_from_history()
+}
+
+/// Put a secret on the clipboard.
+pub fn set_text(text: &str) -> Result<()> {
+    match backend() {
+        Backend::System => exclude_from_history(system()?.set())
+            .text(text)
+            .context("copy to the system clipboard"),
+        Backend::File(path) => {
+            fs::write(&path, text).with_context(|| format!("write {}", path.display()))
+        }
+    }
+}
+
+/// Current clipboard text; empty when it holds nothing (or no text).
+fn get_text() -> Result<String> {
+    match backend() {
+        Backend::System => Ok(system()?.get_text().unwrap_or_default()),
+        Backend::File(path) => Ok(fs::read_to_string(&path).unwrap_or_default()),
+    }
+}
+
+fn clear() -> Result<()> {
+    match backend() {
+        Backend::System => system()?.clear().context("clear the system clipboard"),
+        Backend::File(path) => {
+            fs::write(&path, "").with_context(|| format!("write {}", path.display()))
+        }
+    }
+}
+
+/// Hex SHA-256 of a copied value, used to recognise it later without keeping it.
+pub fn digest(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Clear the clipboard if it still holds the value with this digest.
+pub fn clear_if_unchanged(value_digest: &str) -> Result<bool> {
+    if digest(&get_text()?) != value_digest {
+        return Ok(false);
+    }
+    clear()?;
+    Ok(true)
+}
+
+/// Seconds after which copied values are cleared (0 = never).
+pub fn clear_delay_seconds() -> u32 {
+    app_settings::load_settings()
+        .map(|s| s.security.clipboard_clear_seconds)
+        .unwrap_or(30)
+}
+
+/// Copy a secret and schedule its removal; returns the delay in seconds (0 = kept).
+pub fn copy_secret(text: &str) -> Result<u32> {
+    // Set it here too, so it is there as soon as we return and errors show up
+    set_text(text)?;
+    let delay = clear_delay_seconds();
+    if delay > 0 || (OWNER_MUST_STAY && matches!(backend(), Backend::System)) {
+        spawn_clear_helper(delay, text)?;
+    }
+    Ok(delay)
+}
+
+/// Suffix for "copied" messages describing when the value goes away.
+pub fn clear_note(delay: u32) -> String {
+    if delay == 0 {
+        String::new()
+    } else {
+        format!(" (cleared in {}s)", delay)
+    }
+}
+
+/// Start `safehold clipboard-clear` in the background so the CLI can exit now.
+fn spawn_clear_helper(delay: u32, value: &str) -> Result<()> {
+    let exe = std::env::current_exe().context("locate the safehold executable")?;
+    let mut cmd = Command::new(exe);
+    cmd.args(["--quiet", "clipboard-clear", "--after"])
+        .arg(delay.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Own process group: Ctrl+C in the terminal must not cancel the clearing
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    let mut child = cmd.spawn().context("start the clipboard clearing helper")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(value.as_bytes())
+            .context("hand the value to the clipboard clearing helper")?;
+    }
+    Ok(())
+}
+
+/// Hidden helper command: hold the value read from stdin on the clipboard for
+/// `after` seconds (0 = until something else is copied), then clear it if it
+/// is unchanged.
+pub fn cmd_clipboard_clear(after: u64) -> Result<()> {
+    let mut value = SecretString::default();
+    std::io::stdin()
+        .read_to_string(value.expose_mut())
+        .context("read the copied value")?;
+    let deadline = (after > 0).then(|| Instant::now() + Duration::from_secs(after));
+    if OWNER_MUST_STAY && matches!(backend(), Backend::System) {
+        serve(value.expose(), deadline)?;
+    }
+    let Some(deadline) = deadline else {
+        return Ok(());
+    };
+    std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+    clear_if_unchanged(&digest(value.expose()))?;
+    Ok(())
+}
+
+/// Own the clipboard until `deadline` or until something else is copied.
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+))]
+fn serve(value: &str, deadline: Option<Instant>) -> Result<()> {
+    use arboard::SetExtLinux;
+    // The CLI that set it may already have exited, taking the contents along
+    let mut clipboard = system()?;
+    let set = exclude_from_history(clipboard.set());
+    let set = match deadline {
+        Some(deadline) => set.wait_until(deadline),
+        None => set.wait(),
+    };
+    set.text(value).context("copy to the system clipboard")
+}
+
+#[cfg(not(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+)))]
+fn serve(_value: &str, _deadline: Option<Instant>) -> Result<()> {
+    Ok(())
+}
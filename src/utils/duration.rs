@@ -0,0 +1,86 @@
+//! Human-friendly durations for command-line options
+//!
+//! Accepts a number followed by an optional unit: `s` (default), `m`, `h`,
+//! `d` or `w`, e.g. `90`, `30s`, `10m`, `14d`.
+
+use anyhow::{Result, bail};
+use std::time::Duration;
+
+/// Parse a duration such as `30s`, `10m`, `2h` or `14d`.
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let s = input.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (digits, unit) = s.split_at(split);
+    if digits.is_empty() {
+        bail!(
+            "invalid duration '{}': expected e.g. 30s, 10m, 2h, 14d",
+            input
+        );
+    }
+    let n: u64 = digits
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid duration '{}': number too large", input))?;
+    let secs_per_unit = match unit.trim() {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        other => bail!(
+            "invalid duration '{}': unknown unit '{}' (use s, m, h, d or w)",
+            input,
+            other
+        ),
+    };
+    match n.checked_mul(secs_per_unit) {
+        Some(secs) => Ok(Duration::from_secs(secs)),
+        None => bail!("invalid duration '{}': too large", input),
+    }
+}
+
+/// Render a duration with the largest unit that divides it exactly.
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    for (unit, size) in [("w", 604_800), ("d", 86_400), ("h", 3_600), ("m", 60)] {
+        if secs >= size && secs.is_multiple_of(size) {
+            return format!("{}{}", secs / size, unit);
+        }
+    }
+    format!("{}s", secs)
+}
+
+/// Render a duration in whole days, hours or minutes, rounding down (`3d`, `5h`).
+pub fn format_approx(d: Duration) -> String {
+    let secs = d.as_secs();
+    for (unit, size) in [("d", 86_400), ("h", 3_600), ("m", 60)] {
+        if secs >= size {
+            return format!("{}{}", secs / size, unit);
+        }
+    }
+    format!("{}s", secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(
+            parse_duration("14d").unwrap(),
+            Duration::from_secs(1_209_600)
+        );
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("5y").is_err());
+    }
+
+    #[test]
+    fn formats_with_largest_exact_unit() {
+        assert_eq!(format_duration(Duration::from_secs(7200)), "2h");
+        assert_eq!(format_duration(Duration::from_secs(90)), "90s");
+        assert_eq!(format_approx(Duration::from_secs(90)), "1m");
+        assert_eq!(format_approx(Duration::from_secs(10 * 86_400 + 5)), "10d");
+    }
+}
@@ -2,9 +2,13 @@
 //!
 //! This module contains supporting utility functionality:
 //! - Application settings management
+//! - Clipboard access with automatic clearing
+//! - Duration parsing for command-line options
 //! - Installation and setup logic
 //! - Update checking functionality
 
 pub mod app_settings;
+pub mod clipboard;
+pub mod duration;
 pub mod install;
 pub mod update_checker;
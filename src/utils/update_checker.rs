@@ -13,7 +13,7 @@ pub struct UpdateInfo {
 }
 
 pub fn display_update_notification(update_info: &UpdateInfo) {
-    styles::warn(&format!(
+    styles::warn(format!(
         "Update available! SafeHold {} is now available",
         update_info.latest_version
     ));
@@ -34,10 +34,17 @@ fn create_unlocked_and_add_get() {
         .args(["get", "-p", "project1", "-k", "API_KEY"]);
     cmd.assert().success().stdout("abc123\n");
 
-    // list keys
+    // list keys (masked unless revealed)
     let mut cmd = bin();
     cmd.env("SAFEHOLD_HOME", &home)
         .args(["list", "-p", "project1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("API_KEY=•••• (6 chars)"))
+        .stdout(predicate::str::contains("abc123").not());
+    let mut cmd = bin();
+    cmd.env("SAFEHOLD_HOME", &home)
+        .args(["list", "-p", "project1", "--reveal"]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("API_KEY=abc123"));